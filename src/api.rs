@@ -10,6 +10,7 @@ use serde_json::Value;
 use std::error::Error;
 
 use crate::data::Server;
+use crate::i18n::t;
 
 #[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
 pub enum LoginResult {
@@ -17,6 +18,16 @@ pub enum LoginResult {
     Fail,
     Unknown,
 }
+
+impl LoginResult {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Success => t("login.success"),
+            Self::Fail => t("login.fail"),
+            Self::Unknown => t("login.unknown"),
+        }
+    }
+}
 use self::LoginResult::*;
 pub fn official_login_request(username: &str, password: &str) -> Request {
     #[derive(Serialize)]
@@ -43,7 +54,9 @@ pub fn official_login_promise(username: &str, password: &str) -> Promise<LoginRe
     ehttp::fetch(request, move |result: ehttp::Result<ehttp::Response>| {
         fn f(result: ehttp::Result<ehttp::Response>) -> Result<LoginResult, Box<dyn Error>> {
             #[cfg(test)]
-            println!("{}", result.as_ref().unwrap().text().unwrap());
+            if let Ok(Some(text)) = result.as_ref().map(|x| x.text()) {
+                println!("{}", crate::redact::text(text));
+            }
 
             let response = result?;
             let r = response.text().ok_or("")?;
@@ -131,7 +144,9 @@ pub fn bilibili_login_second(
     ehttp::fetch(request, move |result: ehttp::Result<ehttp::Response>| {
         fn f(result: ehttp::Result<ehttp::Response>) -> Result<LoginResult, Box<dyn Error>> {
             #[cfg(test)]
-            println!("{}", result.as_ref().unwrap().text().unwrap());
+            if let Ok(Some(text)) = result.as_ref().map(|x| x.text()) {
+                println!("{}", crate::redact::text(text));
+            }
 
             let response = result?;
             let r = response.text().ok_or("")?;
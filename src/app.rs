@@ -1,8 +1,33 @@
+use std::collections::HashMap;
+
 use crate::api::{login_promise, LoginResult};
-use crate::data::{Account, AccountBuilder, AccountMode, Server, Setting};
-use chrono::{Local, NaiveDateTime, TimeZone, Timelike};
+use crate::bulk::{BulkEdit, Selection};
+use crate::card::CardHeights;
+use crate::choice;
+use crate::config::{self, Config};
+use crate::csv;
+use crate::data::{self, Account, AccountMode, Server, Setting};
+use crate::file;
+use crate::filter::{self, AccountFilter};
+use crate::grid;
+use crate::help;
+use crate::i18n::{self, t, tf, Lang};
+use crate::json;
+use crate::log::{LogBuffer, LogFilter, LogLevel};
+use crate::plan;
+use crate::qr;
+use crate::redact::{self, Redactor};
+use crate::report::{self, History};
+use crate::runner::{HttpRunner, RunControl, RunEvent, RunRequest, RunState};
+use crate::schedule::{self, Tick};
+use crate::share;
+use crate::shortcut::{self, Action};
+use crate::undo::Undo;
+use crate::validate::{self, Diagnostic, Severity, Target};
+use crate::vault::{self, Vault};
+use chrono::{Datelike, Local, Weekday};
 use egui::{Align, Area, DragValue, FontData, FontDefinitions, FontFamily, Key, Order, TextEdit};
-use egui::{Button, Frame};
+use egui::{Button, Color32, Frame, RichText};
 use egui_extras::{Column, TableBuilder};
 use egui_notify::Toasts;
 use poll_promise::Promise;
@@ -17,6 +42,9 @@ enum Layout {
     Account,
     Setting,
     Help,
+    Log,
+    Schedule,
+    Transfer,
 }
 impl Layout {
     fn toggle_default(&self, target: Layout) -> Self {
@@ -28,6 +56,72 @@ impl Layout {
     }
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+enum TransferFormat {
+    #[default]
+    Script,
+    Csv,
+}
+impl TransferFormat {
+    const ALL: [Self; 2] = [Self::Script, Self::Csv];
+
+    fn str(&self) -> &'static str {
+        match self {
+            Self::Script => t("transfer.script"),
+            Self::Csv => t("transfer.csv"),
+        }
+    }
+}
+
+/// An open JSON editor, parsed again whenever its text changes.
+struct JsonEdit {
+    scope: json::Scope,
+    text: String,
+    parsed: Result<serde_json::Value, String>,
+    changes: Vec<json::Change>,
+    /// The undo revision `changes` was found against, so edits made elsewhere
+    /// while the editor is open show up in the diff.
+    revision: u64,
+}
+
+/// The QR codes of an export, shown one at a time.
+struct QrView {
+    codes: Vec<qr::Code>,
+    page: usize,
+}
+
+/// A shared config merged into this one, waiting for confirmation.
+struct ShareImport {
+    document: serde_json::Value,
+    account: Vec<Account>,
+    setting: Setting,
+    changes: Vec<json::Change>,
+    /// The undo revision the merge was made at, merged again once the config
+    /// changes so applying never undoes edits made meanwhile.
+    revision: u64,
+}
+
+impl ShareImport {
+    fn new(
+        document: serde_json::Value,
+        account: &[Account],
+        setting: &Setting,
+        revision: u64,
+    ) -> Self {
+        let (merged, merged_setting) = share::merge(&document, account, setting);
+        let scope = json::Scope::All;
+        let before = json::document(scope, account, setting);
+        let after = json::document(scope, &merged, &merged_setting);
+        Self {
+            changes: json::diff(scope, &before, &after),
+            document,
+            account: merged,
+            setting: merged_setting,
+            revision,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(default)]
 pub struct MyApp {
@@ -39,12 +133,112 @@ pub struct MyApp {
     logining: Option<Promise<LoginResult>>,
     #[serde(skip)]
     toast: Toasts,
+    #[serde(skip)]
+    run: RunControl,
+    #[serde(skip)]
+    log: LogBuffer,
+    #[serde(skip)]
+    log_filter: LogFilter,
+    log_follow: bool,
+    history: History,
+    #[serde(skip)]
+    report_text: String,
+    #[serde(skip)]
+    schedule: Option<Result<Vec<Tick>, String>>,
+    #[serde(skip)]
+    preview_weekday: Option<Weekday>,
+    #[serde(skip)]
+    help_search: String,
+    #[serde(skip)]
+    help_focus: Option<&'static str>,
+    #[serde(skip)]
+    help_scroll: bool,
+    #[serde(skip)]
+    account_filter: AccountFilter,
+    account_grid: bool,
+    #[serde(skip)]
+    grid_sort: Option<(grid::Column, bool)>,
+    #[serde(skip)]
+    selection: Selection,
+    #[serde(skip)]
+    selection_text: String,
+    #[serde(skip)]
+    bulk: Option<BulkEdit>,
+    #[serde(skip)]
+    clipboard: Option<Account>,
+    paste_credentials: bool,
+    #[serde(skip)]
+    slot_target: usize,
+    #[serde(skip)]
+    card_heights: CardHeights,
+    #[serde(skip)]
+    scroll_pending: bool,
+    #[serde(skip)]
+    focus_account: Option<usize>,
+    #[serde(skip)]
+    jump: Option<String>,
+    #[serde(skip)]
+    shortcuts_open: bool,
+    #[serde(skip)]
+    undo: Undo,
+    #[serde(skip)]
+    problems: Option<Vec<Diagnostic>>,
+    #[serde(skip)]
+    transfer_text: String,
+    #[serde(skip)]
+    transfer_format: TransferFormat,
+    #[serde(skip)]
+    csv_import: Option<csv::Import>,
+    #[serde(skip)]
+    json_edit: Option<JsonEdit>,
+    config_path: String,
+    #[serde(skip)]
+    config_path_edit: String,
+    #[serde(skip)]
+    config_opening: Option<file::Opened>,
+    #[serde(skip)]
+    share_code: String,
+    #[serde(skip)]
+    share_text: String,
+    #[serde(skip)]
+    share_import: Option<ShareImport>,
+    #[serde(skip)]
+    qr: Option<QrView>,
+    hide_secrets: bool,
+    #[serde(skip)]
+    vault: Option<Vault>,
+    #[serde(skip)]
+    vault_pass: String,
+    #[serde(skip)]
+    vault_confirm: String,
+    #[serde(skip)]
+    export_plain: bool,
+    #[serde(skip)]
+    unlock_later: bool,
+    /// A passphrase being derived into a vault, `true` when it unlocks the
+    /// config rather than enabling encryption.
+    #[serde(skip)]
+    vault_deriving: Option<(bool, vault::Deriving)>,
+    /// Set where the config changes without user input, such as a file
+    /// finishing loading, so undo looks at it even on a frame without input.
+    #[serde(skip)]
+    config_changed: bool,
+    /// Built for the config as of an undo revision.
+    #[serde(skip)]
+    redactor: Option<(u64, Redactor)>,
+    /// [`validate::account`] of the accounts shown so far, as of an undo
+    /// revision.
+    #[serde(skip)]
+    diagnostics: (u64, HashMap<usize, Vec<Diagnostic>>),
+    /// [`filter::lowercase`] of the accounts as of an undo revision.
+    #[serde(skip)]
+    usernames: Option<(u64, Vec<String>)>,
 }
 
 impl Default for MyApp {
     fn default() -> Self {
-        let mut account = vec![AccountBuilder::default().inherit(false).build().unwrap()];
-        account.extend(vec![AccountBuilder::default().build().unwrap(); 9999]);
+        let mut account = vec![data::default_account(0)];
+        account.extend(vec![data::default_account(1); data::TOTAL - 1]);
         let total = account.len();
         let setting = Setting::default().multi_account_choice(format!("0-{total}"));
         Self {
@@ -54,6 +248,55 @@ impl Default for MyApp {
             scroll_to_account: 0,
             logining: None,
             toast: Default::default(),
+            run: Default::default(),
+            log: Default::default(),
+            log_filter: Default::default(),
+            log_follow: true,
+            history: Default::default(),
+            report_text: String::new(),
+            schedule: None,
+            preview_weekday: None,
+            help_search: String::new(),
+            help_focus: None,
+            help_scroll: false,
+            account_filter: Default::default(),
+            account_grid: false,
+            grid_sort: None,
+            selection: Default::default(),
+            selection_text: String::new(),
+            bulk: None,
+            clipboard: None,
+            paste_credentials: false,
+            slot_target: 0,
+            card_heights: Default::default(),
+            scroll_pending: false,
+            focus_account: None,
+            jump: None,
+            shortcuts_open: false,
+            undo: Default::default(),
+            problems: None,
+            transfer_text: String::new(),
+            transfer_format: Default::default(),
+            csv_import: None,
+            json_edit: None,
+            config_path: "mizuki_config.json".into(),
+            config_path_edit: String::new(),
+            config_opening: None,
+            share_code: String::new(),
+            share_text: String::new(),
+            share_import: None,
+            qr: None,
+            hide_secrets: false,
+            vault: None,
+            vault_pass: String::new(),
+            vault_confirm: String::new(),
+            export_plain: false,
+            unlock_later: false,
+            vault_deriving: None,
+            config_changed: true,
+            redactor: None,
+            usernames: None,
+            diagnostics: Default::default(),
         }
     }
 }
@@ -61,7 +304,11 @@ impl Default for MyApp {
 impl MyApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         Self::set_style(&cc.egui_ctx);
-        Default::default()
+        let mut app = Self::default();
+        if let Some(hash) = share::take_from_url() {
+            app.preview_share(&hash);
+        }
+        app
     }
 
     pub fn set_style(ctx: &egui::Context) {
@@ -103,10 +350,154 @@ impl MyApp {
         ctx.set_style(style)
     }
 
+    /// A field label showing the field's help on hover and opening it on click.
+    fn label(ui: &mut egui::Ui, state: &mut Self, text: &str, key: &'static str) {
+        let response = ui
+            .add(egui::Label::new(text).sense(egui::Sense {
+                click: true,
+                drag: false,
+                // keep Tab moving between the fields themselves
+                focusable: false,
+            }))
+            .on_hover_text(help::text(key))
+            .on_disabled_hover_text(help::text(key));
+        if response.clicked() {
+            state.show_help(key);
+        }
+    }
+
+    /// A single-line edit for a secret, masked unless revealed with the button
+    /// next to it. While all secrets are hidden it cannot be revealed.
+    fn secret(
+        ui: &mut egui::Ui,
+        hide_all: bool,
+        text: &mut String,
+        id: impl std::hash::Hash,
+    ) -> egui::Response {
+        let id = ui.make_persistent_id(("reveal", id));
+        let shown = !hide_all && ui.data().get_temp::<bool>(id).unwrap_or(false);
+        let response = ui.add(TextEdit::singleline(text).password(!shown));
+        let reveal = ui
+            .add_enabled(!hide_all, egui::SelectableLabel::new(shown, "👁"))
+            .on_hover_text(t("secret.reveal"));
+        if reveal.clicked() {
+            ui.data().insert_temp(id, !shown);
+        }
+        response
+    }
+
+    /// `text` as shown while secrets are hidden.
+    fn masked(text: &str, hide: bool) -> String {
+        if hide && !text.is_empty() {
+            "•".repeat(8)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Outlines a field widget with the most severe problem of `field` in
+    /// `list` and shows the problem on hover.
+    fn mark(
+        ui: &egui::Ui,
+        response: egui::Response,
+        list: &[Diagnostic],
+        field: &str,
+    ) -> egui::Response {
+        let diagnostic = match validate::worst(list, field) {
+            Some(x) => x,
+            None => return response,
+        };
+        let color = Self::severity_color(diagnostic.severity);
+        ui.painter().rect_stroke(
+            response.rect.expand(1.0),
+            2.0,
+            egui::Stroke::new(1.0, color),
+        );
+        let text = list
+            .iter()
+            .filter(|x| x.field == field)
+            .map(|x| x.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        response
+            .on_hover_text(text.clone())
+            .on_disabled_hover_text(text)
+    }
+
+    fn severity_color(severity: Severity) -> Color32 {
+        match severity {
+            Severity::Warning => Color32::YELLOW,
+            Severity::Error => Color32::RED,
+        }
+    }
+
+    fn show_help(&mut self, key: &'static str) {
+        self.layout = Layout::Help;
+        self.help_search.clear();
+        self.help_focus = Some(key);
+        self.help_scroll = true;
+    }
+
+    fn help(ui: &mut egui::Ui, state: &mut Self) {
+        ui.horizontal(|ui| {
+            ui.label(t("search"));
+            ui.text_edit_singleline(&mut state.help_search);
+            if ui.button(t("shortcut.cheat_sheet")).clicked() {
+                state.shortcuts_open = !state.shortcuts_open;
+            }
+        });
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (title, entries) in [
+                (t("section.account"), help::ACCOUNT),
+                (t("section.setting"), help::SETTING),
+            ] {
+                let entries: Vec<_> = entries
+                    .iter()
+                    .filter(|x| x.matches(&state.help_search))
+                    .collect();
+                if entries.is_empty() {
+                    continue;
+                }
+                ui.heading(title);
+                for entry in entries {
+                    let mut frame = Frame::group(ui.style());
+                    let focused = state.help_focus == Some(entry.key);
+                    if focused {
+                        frame.stroke.color = ui.visuals().selection.stroke.color;
+                    }
+                    let response = frame
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.horizontal(|ui| {
+                                ui.strong(entry.label());
+                                ui.weak(entry.key);
+                            });
+                            ui.label(entry.text());
+                        })
+                        .response;
+                    if focused && state.help_scroll {
+                        response.scroll_to_me(Some(Align::Center));
+                        state.help_scroll = false;
+                    }
+                }
+            }
+        });
+    }
+
     fn one_account(ui: &mut egui::Ui, state: &mut Self, idx: usize) {
+        let diagnostics = state.account_diagnostics(idx);
         if state.setting.multi_account {
             ui.horizontal(|ui| {
                 ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
+                    let mut selected = state.selection.contains(idx);
+                    if ui
+                        .checkbox(&mut selected, "")
+                        .on_hover_text(t("bulk.toggle"))
+                        .clicked()
+                    {
+                        let shift = ui.input().modifiers.shift;
+                        state.selection.click(idx, shift);
+                    }
                     if ui.button(format!("#{}", idx)).clicked() {
                         let base = state
                             .setting
@@ -117,21 +508,42 @@ impl MyApp {
                             .trim();
                         state.setting.multi_account_choice = format!("{base} #{idx}");
                     }
+                    Self::slot(ui, state, idx);
+                    Self::history(ui, state, idx);
                 })
             });
             ui.horizontal(|ui| {
-                ui.label(format!("账号"));
-                ui.text_edit_singleline(&mut state.account[idx].username);
+                Self::label(ui, state, t("field.username"), "username");
+                let response = ui.add(
+                    TextEdit::singleline(&mut state.account[idx].username)
+                        .password(state.hide_secrets),
+                );
+                let response = Self::mark(ui, response, &diagnostics, "username");
+                if state.focus_account == Some(idx) {
+                    response.request_focus();
+                    state.focus_account = None;
+                }
             });
             ui.horizontal(|ui| {
-                ui.label(format!("密码"));
-                ui.text_edit_singleline(&mut state.account[idx].password);
+                Self::label(ui, state, t("field.password"), "password");
+                let hide = state.hide_secrets;
+                let text = &mut state.account[idx].password;
+                let response = Self::secret(ui, hide, text, ("password", idx));
+                Self::mark(ui, response, &diagnostics, "password");
             });
         }
         ui.horizontal(|ui| {
-            ui.label("服务");
-            ui.radio_value(&mut state.account[idx].server, Server::Official, "官服");
-            ui.radio_value(&mut state.account[idx].server, Server::Bilibili, "B服");
+            Self::label(ui, state, t("field.server"), "server");
+            ui.radio_value(
+                &mut state.account[idx].server,
+                Server::Official,
+                t("server.official"),
+            );
+            ui.radio_value(
+                &mut state.account[idx].server,
+                Server::Bilibili,
+                t("server.bilibili"),
+            );
             if !state.setting.multi_account {
                 return;
             }
@@ -139,12 +551,7 @@ impl MyApp {
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 if let Some(promise) = &state.logining {
                     if let Some(result) = promise.ready() {
-                        let result = match result {
-                            LoginResult::Fail => "无效",
-                            LoginResult::Success => "有效",
-                            LoginResult::Unknown => "未知",
-                        };
-                        state.toast.info(result);
+                        state.toast.info(result.str());
                         // ui.label(result);
                         state.logining = None;
                     } else {
@@ -153,7 +560,7 @@ impl MyApp {
                     return;
                 }
 
-                let button = ui.button("测试");
+                let button = ui.button(t("button.test"));
                 if button.clicked() {
                     // let promise = login_promise(
                     //     &state.account[idx].username,
@@ -168,10 +575,11 @@ impl MyApp {
             });
         });
         ui.horizontal(|ui| {
-            ui.label("模式");
+            Self::label(ui, state, t("field.mode"), "mode");
             if ui.button(state.account[idx].mode.str()).clicked() {
                 state.account[idx].mode = state.account[idx].mode.next();
             }
+            Self::preview(ui, state, idx);
 
             if !state.setting.multi_account {
                 return;
@@ -180,9 +588,9 @@ impl MyApp {
             if state.account[idx].mode == AccountMode::Daily {
                 if ui
                     .button(if state.account[idx].inherit {
-                        "继承"
+                        t("inherit.on")
                     } else {
-                        "独立"
+                        t("inherit.off")
                     })
                     .clicked()
                 {
@@ -190,18 +598,19 @@ impl MyApp {
                 }
                 if state.account[idx].inherit {
                     let total = state.account.len();
-                    ui.add(
+                    let response = ui.add(
                         DragValue::new(&mut state.account[idx].inherit_index)
-                            .prefix("账号")
-                            .clamp_range(0..=total - 1),
+                            .prefix(t("inherit.prefix"))
+                            .clamp_range(data::range("inherit_index", total)),
                     );
+                    Self::mark(ui, response, &diagnostics, "inherit_index");
                 }
             }
         });
         match state.account[idx].mode {
             AccountMode::Daily => {
                 ui.add_enabled_ui(!state.account[idx].inherit, |ui| {
-                    Self::one_account_daily(ui, state, idx);
+                    Self::one_account_daily(ui, state, idx, &diagnostics);
                 });
             }
             AccountMode::ZL => Self::one_account_zl(ui, state, idx),
@@ -209,50 +618,267 @@ impl MyApp {
         };
     }
 
+    /// Copy, paste, reset and rearrangement of the account slot `idx`.
+    fn slot(ui: &mut egui::Ui, state: &mut Self, idx: usize) {
+        let total = state.account.len();
+        ui.menu_button("…", |ui| {
+            if ui.button(t("slot.copy")).clicked() {
+                state.clipboard = Some(state.account[idx].clone());
+                ui.close_menu();
+            }
+            let paste = Button::new(t("slot.paste"));
+            if ui.add_enabled(state.clipboard.is_some(), paste).clicked() {
+                if let Some(x) = &state.clipboard {
+                    state.account[idx].paste(x, state.paste_credentials);
+                }
+                ui.close_menu();
+            }
+            ui.checkbox(&mut state.paste_credentials, t("slot.credentials"));
+            if ui.button(t("slot.reset")).clicked() {
+                state.account[idx] = data::default_account(idx);
+                ui.close_menu();
+            }
+            if ui.button(t("json.edit")).clicked() {
+                state.open_json(json::Scope::Account(idx));
+                ui.close_menu();
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(
+                    DragValue::new(&mut state.slot_target)
+                        .prefix(t("inherit.prefix"))
+                        .clamp_range(0..=total - 1),
+                );
+                let target = state.slot_target;
+                if ui.button(t("slot.swap")).clicked() {
+                    let map = data::swap(&mut state.account, idx, target);
+                    state.remap_slots(idx.min(target)..=idx.max(target), map);
+                    ui.close_menu();
+                }
+                if ui.button(t("slot.move")).clicked() {
+                    let map = data::move_to(&mut state.account, idx, target);
+                    state.remap_slots(idx.min(target)..=idx.max(target), map);
+                    ui.close_menu();
+                }
+            });
+        });
+    }
+
+    /// Rebuilds the redactor if the config changed since it was built.
+    fn update_redactor(&mut self) {
+        let revision = self.undo.revision();
+        if !matches!(&self.redactor, Some((x, _)) if *x == revision) {
+            let redactor = Redactor::new(&self.account, &self.setting);
+            self.redactor = Some((revision, redactor));
+        }
+    }
+
+    /// The problems of account `idx`, found again only once the config changed.
+    fn account_diagnostics(&mut self, idx: usize) -> Vec<Diagnostic> {
+        let revision = self.undo.revision();
+        if self.diagnostics.0 != revision {
+            self.diagnostics = (revision, HashMap::new());
+        }
+        let (account, setting) = (&self.account, &self.setting);
+        self.diagnostics
+            .1
+            .entry(idx)
+            .or_insert_with(|| validate::account(account, idx, setting))
+            .clone()
+    }
+
+    /// Lowercases the usernames again if the config changed since.
+    fn update_usernames(&mut self) {
+        let revision = self.undo.revision();
+        if !matches!(&self.usernames, Some((x, _)) if *x == revision) {
+            self.usernames = Some((revision, filter::lowercase(&self.account)));
+        }
+    }
+
+    /// Points everything keyed by account index at the accounts' new slots
+    /// after they moved within `window`.
+    fn remap_slots(
+        &mut self,
+        window: std::ops::RangeInclusive<usize>,
+        map: impl Fn(usize) -> usize + Copy,
+    ) {
+        self.history.remap(map);
+        self.selection.remap(map);
+        let choice = &mut self.setting.multi_account_choice;
+        *choice = choice::remap(choice, window, map);
+        if let Ok(clue) = self.setting.multi_account_clue.trim().parse() {
+            self.setting.multi_account_clue = map(clue).to_string();
+        }
+    }
+
+    fn history(ui: &mut egui::Ui, state: &mut Self, idx: usize) {
+        let text = match state.history.last(idx) {
+            Some(report) if report.ok() => {
+                RichText::new(tf("history.ok", &[&report.time])).color(Color32::GREEN)
+            }
+            Some(report) => {
+                RichText::new(tf("history.failed", &[&report.time])).color(Color32::RED)
+            }
+            None => RichText::new(t("history.none")).weak(),
+        };
+        let popup_id = ui.make_persistent_id(("history", idx));
+        let button_response = ui.add(Button::new(text).small());
+        if button_response.clicked() {
+            ui.memory().toggle_popup(popup_id);
+        }
+        if ui.memory().is_popup_open(popup_id) {
+            let area_response = Area::new(popup_id)
+                .order(Order::Foreground)
+                .fixed_pos(button_response.rect.min)
+                .constrain(true)
+                .show(ui.ctx(), |ui| {
+                    Frame::popup(ui.style()).show(ui, |ui| {
+                        let reports = state.history.get(idx);
+                        if reports.is_empty() {
+                            ui.label(t("history.empty"));
+                            return;
+                        }
+                        egui::ScrollArea::vertical()
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                for report in reports.iter().rev() {
+                                    let text = RichText::new(report.to_text());
+                                    ui.label(if report.ok() {
+                                        text
+                                    } else {
+                                        text.color(Color32::RED)
+                                    });
+                                }
+                            });
+                        if ui.button(t("button.clear")).clicked() {
+                            state.history.clear(idx);
+                        }
+                    });
+                })
+                .response;
+
+            if !button_response.clicked()
+                && (ui.input().key_pressed(Key::Escape) || area_response.clicked_elsewhere())
+            {
+                ui.memory().close_popup();
+            }
+        }
+    }
+
+    fn preview(ui: &mut egui::Ui, state: &mut Self, idx: usize) {
+        let popup_id = ui.make_persistent_id(("preview", idx));
+        let button_response = ui.button(t("button.preview"));
+        if button_response.clicked() {
+            ui.memory().toggle_popup(popup_id);
+        }
+        if !ui.memory().is_popup_open(popup_id) {
+            return;
+        }
+        let area_response = Area::new(popup_id)
+            .order(Order::Foreground)
+            .fixed_pos(button_response.rect.left_bottom())
+            .constrain(true)
+            .show(ui.ctx(), |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(WIDTH);
+                    let weekday = state
+                        .preview_weekday
+                        .get_or_insert_with(|| Local::now().weekday());
+                    ui.horizontal_wrapped(|ui| {
+                        let mut day = Weekday::Mon;
+                        for name in schedule::WEEKDAY {
+                            let name = t(name);
+                            ui.selectable_value(weekday, day, name);
+                            day = day.succ();
+                        }
+                    });
+                    let weekday = *weekday;
+                    let account = data::resolve(&state.account, idx);
+                    match plan::plan(&account, weekday) {
+                        None => {
+                            ui.label(t("preview.rest"));
+                        }
+                        Some(steps) if steps.is_empty() => {
+                            ui.label(t("preview.empty"));
+                        }
+                        Some(steps) => {
+                            for (i, step) in steps.iter().enumerate() {
+                                ui.label(format!("{}. {}", i + 1, step.str()));
+                            }
+                        }
+                    }
+                });
+            })
+            .response;
+
+        if !button_response.clicked()
+            && (ui.input().key_pressed(Key::Escape) || area_response.clicked_elsewhere())
+        {
+            ui.memory().close_popup();
+        }
+    }
+
     fn one_account_zl(ui: &mut egui::Ui, state: &mut Self, idx: usize) {
         ui.horizontal(|ui| {
-            ui.checkbox(&mut state.account[idx].zl_level, "等级(蜡烛)");
-            ui.add(DragValue::new(&mut state.account[idx].zl_max_level).clamp_range(0..=9999));
-            ui.checkbox(&mut state.account[idx].zl_coin, "源石锭");
-            ui.add(DragValue::new(&mut state.account[idx].zl_max_coin).clamp_range(0..=9999));
+            ui.checkbox(&mut state.account[idx].zl_level, t("zl.level"))
+                .on_hover_text(help::text("zl_level"));
+            let range = data::range("zl_max_level", state.account.len());
+            ui.add(DragValue::new(&mut state.account[idx].zl_max_level).clamp_range(range));
+            ui.checkbox(&mut state.account[idx].zl_coin, t("zl.coin"))
+                .on_hover_text(help::text("zl_coin"));
+            let range = data::range("zl_max_coin", state.account.len());
+            ui.add(DragValue::new(&mut state.account[idx].zl_max_coin).clamp_range(range));
         });
-        ui.checkbox(&mut state.account[idx].zl_no_waste, "先做日常");
+        ui.checkbox(&mut state.account[idx].zl_no_waste, t("zl.no_waste"))
+            .on_hover_text(help::text("zl_no_waste"));
     }
 
     fn one_account_recruit(ui: &mut egui::Ui, state: &mut Self, idx: usize) {
         ui.horizontal(|ui| {
-            ui.label("招募");
+            ui.label(t("field.recruit"));
             let mut always_true = true;
             ui.add_enabled_ui(false, |ui| {
-                ui.checkbox(&mut always_true, "其他");
+                ui.checkbox(&mut always_true, t("recruit.other"));
             });
-            ui.checkbox(&mut state.account[idx].recruit_recruit1, "小车");
-            ui.checkbox(&mut state.account[idx].recruit_recruit4, "四星");
-            ui.checkbox(&mut state.account[idx].recruit_recruit5, "五星");
-            ui.checkbox(&mut state.account[idx].recruit_recruit6, "六星");
+            ui.checkbox(&mut state.account[idx].recruit_recruit1, t("recruit.robot"))
+                .on_hover_text(help::text("recruit_recruit1"));
+            ui.checkbox(&mut state.account[idx].recruit_recruit4, t("recruit.star4"))
+                .on_hover_text(help::text("recruit_recruit4"));
+            ui.checkbox(&mut state.account[idx].recruit_recruit5, t("recruit.star5"))
+                .on_hover_text(help::text("recruit_recruit5"));
+            ui.checkbox(&mut state.account[idx].recruit_recruit6, t("recruit.star6"))
+                .on_hover_text(help::text("recruit_recruit6"));
         });
     }
 
-    fn one_account_daily(ui: &mut egui::Ui, state: &mut Self, idx: usize) {
+    fn one_account_daily(
+        ui: &mut egui::Ui,
+        state: &mut Self,
+        idx: usize,
+        diagnostics: &[Diagnostic],
+    ) {
         ui.add_enabled_ui(state.account[idx].job_fight, |ui| {
             ui.horizontal(|ui| {
-                ui.label("关卡");
-                ui.text_edit_singleline(&mut state.account[idx].fight);
+                Self::label(ui, state, t("field.fight"), "fight");
+                let response = ui.text_edit_singleline(&mut state.account[idx].fight);
+                Self::mark(ui, response, diagnostics, "fight");
             });
             ui.horizontal(|ui| {
-                ui.label("吃药");
+                Self::label(ui, state, t("field.max_drug"), "max_drug");
+                let range = data::range("max_drug", state.account.len());
                 ui.add(
                     DragValue::new(&mut state.account[idx].max_drug)
-                        .clamp_range(0..=99)
-                        .suffix("次"),
+                        .clamp_range(range)
+                        .suffix(t("unit.times")),
                 );
-                ui.label("石头");
+                Self::label(ui, state, t("field.max_stone"), "max_stone");
+                let range = data::range("max_stone", state.account.len());
                 ui.add(
                     DragValue::new(&mut state.account[idx].max_stone)
-                        .clamp_range(0..=99)
-                        .suffix("次"),
+                        .clamp_range(range)
+                        .suffix(t("unit.times")),
                 );
-                ui.label("到期");
+                Self::label(ui, state, t("field.max_drug_day"), "max_drug_day");
 
                 let mut txt: String = state.account[idx]
                     .max_drug_day
@@ -303,12 +929,12 @@ impl MyApp {
                                         let len = x.len();
                                         for i in 0..len {
                                             // ui.horizontal(|ui| {
-                                            ui.label(format!("{}天", len - i - 1));
+                                            ui.label(tf("unit.days", &[&(len - i - 1)]));
                                             ui.add(
                                                 DragValue::new(
                                                     &mut state.account[idx].max_drug_day[i],
                                                 )
-                                                .suffix("个")
+                                                .suffix(t("unit.count"))
                                                 .clamp_range(0..=99),
                                             );
                                             ui.end_row();
@@ -339,14 +965,14 @@ impl MyApp {
         });
         ui.add_enabled_ui(state.account[idx].job_shop, |ui| {
             ui.horizontal(|ui| {
-                ui.label("多买");
+                Self::label(ui, state, t("field.prefer_goods"), "prefer_goods");
                 let txt =
                     TextEdit::singleline(&mut state.account[idx].prefer_goods).desired_width(100.0);
                 ui.add(txt);
                 // ui.text_edit_singleline(&mut state.account[idx].prefer_goods);
                 // });
                 // ui.horizontal(|ui| {
-                ui.label("少买");
+                Self::label(ui, state, t("field.dislike_goods"), "dislike_goods");
                 let txt = TextEdit::singleline(&mut state.account[idx].dislike_goods)
                     .desired_width(100.0);
                 ui.add(txt);
@@ -355,69 +981,82 @@ impl MyApp {
 
         ui.add_enabled_ui(state.account[idx].job_recruit, |ui| {
             ui.horizontal(|ui| {
-                ui.label("招募");
-                ui.checkbox(&mut state.account[idx].recruit0, "其他");
-                ui.checkbox(&mut state.account[idx].recruit1, "小车");
-                ui.checkbox(&mut state.account[idx].recruit4, "四星");
-                ui.checkbox(&mut state.account[idx].recruit5, "五星");
-                ui.checkbox(&mut state.account[idx].recruit6, "六星");
+                ui.label(t("field.recruit"));
+                ui.checkbox(&mut state.account[idx].recruit0, t("recruit.other"))
+                    .on_hover_text(help::text("recruit0"));
+                ui.checkbox(&mut state.account[idx].recruit1, t("recruit.robot"))
+                    .on_hover_text(help::text("recruit1"));
+                ui.checkbox(&mut state.account[idx].recruit4, t("recruit.star4"))
+                    .on_hover_text(help::text("recruit4"));
+                ui.checkbox(&mut state.account[idx].recruit5, t("recruit.star5"))
+                    .on_hover_text(help::text("recruit5"));
+                ui.checkbox(&mut state.account[idx].recruit6, t("recruit.star6"))
+                    .on_hover_text(help::text("recruit6"));
             });
         });
 
         ui.horizontal(|ui| {
-            ui.label("任务");
+            ui.label(t("field.jobs"));
 
             ui.vertical(|ui| {
                 ui.horizontal(|ui| {
-                    ui.checkbox(&mut state.account[idx].job_mail, "邮件");
-                    ui.checkbox(&mut state.account[idx].job_fight, "作战");
-                    ui.checkbox(&mut state.account[idx].job_friend, "好友");
-                    ui.checkbox(&mut state.account[idx].job_gain, "收菜");
-                    ui.checkbox(&mut state.account[idx].job_shift, "换班");
+                    ui.checkbox(&mut state.account[idx].job_mail, t("job.mail"))
+                        .on_hover_text(help::text("job_mail"));
+                    ui.checkbox(&mut state.account[idx].job_fight, t("job.fight"))
+                        .on_hover_text(help::text("job_fight"));
+                    ui.checkbox(&mut state.account[idx].job_friend, t("job.friend"))
+                        .on_hover_text(help::text("job_friend"));
+                    ui.checkbox(&mut state.account[idx].job_gain, t("job.gain"))
+                        .on_hover_text(help::text("job_gain"));
+                    ui.checkbox(&mut state.account[idx].job_shift, t("job.shift"))
+                        .on_hover_text(help::text("job_shift"));
                 });
                 ui.horizontal(|ui| {
-                    ui.checkbox(&mut state.account[idx].job_manu, "加速");
-                    ui.checkbox(&mut state.account[idx].job_clue, "线索");
-                    ui.checkbox(&mut state.account[idx].job_assist, "副手");
-                    ui.checkbox(&mut state.account[idx].job_shop, "信交");
-                    ui.checkbox(&mut state.account[idx].job_recruit, "公招");
+                    ui.checkbox(&mut state.account[idx].job_manu, t("job.manu"))
+                        .on_hover_text(help::text("job_manu"));
+                    ui.checkbox(&mut state.account[idx].job_clue, t("job.clue"))
+                        .on_hover_text(help::text("job_clue"));
+                    ui.checkbox(&mut state.account[idx].job_assist, t("job.assist"))
+                        .on_hover_text(help::text("job_assist"));
+                    ui.checkbox(&mut state.account[idx].job_shop, t("job.shop"))
+                        .on_hover_text(help::text("job_shop"));
+                    ui.checkbox(&mut state.account[idx].job_recruit, t("job.recruit"))
+                        .on_hover_text(help::text("job_recruit"));
                 });
                 ui.horizontal(|ui| {
-                    ui.checkbox(&mut state.account[idx].job_task, "任务");
-                    ui.checkbox(&mut state.account[idx].job_activity, "活动");
+                    ui.checkbox(&mut state.account[idx].job_task, t("job.task"))
+                        .on_hover_text(help::text("job_task"));
+                    ui.checkbox(&mut state.account[idx].job_activity, t("job.activity"))
+                        .on_hover_text(help::text("job_activity"));
                 });
             });
         });
 
         ui.horizontal(|ui| {
-            ui.label("时间");
+            ui.label(t("field.time"));
             ui.vertical(|ui| {
                 ui.horizontal(|ui| {
-                    ui.checkbox(&mut state.account[idx].allow_monday, "周一");
-                    ui.checkbox(&mut state.account[idx].allow_tuesday, "周二");
-                    ui.checkbox(&mut state.account[idx].allow_wednesday, "周三");
-                    ui.checkbox(&mut state.account[idx].allow_thursday, "周四");
-                    ui.checkbox(&mut state.account[idx].allow_friday, "周五");
+                    ui.checkbox(&mut state.account[idx].allow_monday, t("weekday.mon"))
+                        .on_hover_text(help::text("allow_monday"));
+                    ui.checkbox(&mut state.account[idx].allow_tuesday, t("weekday.tue"))
+                        .on_hover_text(help::text("allow_tuesday"));
+                    ui.checkbox(&mut state.account[idx].allow_wednesday, t("weekday.wed"))
+                        .on_hover_text(help::text("allow_wednesday"));
+                    ui.checkbox(&mut state.account[idx].allow_thursday, t("weekday.thu"))
+                        .on_hover_text(help::text("allow_thursday"));
+                    ui.checkbox(&mut state.account[idx].allow_friday, t("weekday.fri"))
+                        .on_hover_text(help::text("allow_friday"));
                 });
                 ui.horizontal(|ui| {
-                    ui.checkbox(&mut state.account[idx].allow_saturday, "周六");
-                    ui.checkbox(&mut state.account[idx].allow_sunday, "周日");
+                    ui.checkbox(&mut state.account[idx].allow_saturday, t("weekday.sat"))
+                        .on_hover_text(help::text("allow_saturday"));
+                    ui.checkbox(&mut state.account[idx].allow_sunday, t("weekday.sun"))
+                        .on_hover_text(help::text("allow_sunday"));
                     let txt = TextEdit::singleline(&mut state.account[idx].allow_after)
                         .desired_width(120.0);
                     let response = ui.add(txt);
-                    if response.lost_focus() {
-                        let dt = &state.account[idx].allow_after;
-                        let dt = NaiveDateTime::parse_from_str(dt, "%Y-%m-%d %H:%M")
-                            .map(|dt| Local.from_local_datetime(&dt).unwrap())
-                            .unwrap_or(
-                                Local::now()
-                                    .with_hour(0)
-                                    .and_then(|x| x.with_minute(0))
-                                    .unwrap(),
-                            );
-                        state.account[idx].allow_after = dt.format("%Y-%m-%d %H:%M").to_string()
-                    }
-                    ui.label("起");
+                    Self::mark(ui, response, diagnostics, "allow_after");
+                    Self::label(ui, state, t("field.allow_after"), "allow_after");
 
                     // use chrono::{offset::Utc, DateTime, NaiveDate, NaiveDateTime, NaiveTime};
                     // use egui_datepicker::DatePicker;
@@ -439,49 +1078,409 @@ impl MyApp {
         Self::one_account(ui, state, idx);
     }
 
+    /// A combo box choosing one of `options` or none of them, shown as all.
+    fn select<T: PartialEq + Clone>(
+        ui: &mut egui::Ui,
+        id: &str,
+        value: &mut Option<T>,
+        options: &[T],
+        name: impl Fn(&T) -> String,
+    ) {
+        egui::ComboBox::from_id_source(id)
+            .selected_text(match value {
+                Some(x) => name(x),
+                None => t("filter.all").into(),
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(value, None, t("filter.all"));
+                for x in options {
+                    ui.selectable_value(value, Some(x.clone()), name(x));
+                }
+            });
+    }
+
+    fn account_filter(ui: &mut egui::Ui, state: &mut Self, matched: usize) {
+        let total = state.account.len();
+        let filter = &mut state.account_filter;
+        ui.horizontal(|ui| {
+            ui.label(t("search"));
+            ui.add(TextEdit::singleline(&mut filter.username).desired_width(120.0));
+            ui.checkbox(&mut filter.non_empty, t("filter.non_empty"));
+            ui.label(format!("{matched}/{total}"));
+            if !filter.is_empty() && ui.button(t("button.clear")).clicked() {
+                *filter = Default::default();
+            }
+            ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
+                let view = if state.account_grid {
+                    "view.card"
+                } else {
+                    "view.grid"
+                };
+                if ui.button(t(view)).clicked() {
+                    state.account_grid = !state.account_grid;
+                }
+            });
+        });
+        ui.horizontal_wrapped(|ui| {
+            let server = [Server::Official, Server::Bilibili];
+            Self::select(ui, "filter_server", &mut filter.server, &server, |x| {
+                x.clone().str()
+            });
+            let mode = [AccountMode::Daily, AccountMode::ZL, AccountMode::Recruit];
+            Self::select(ui, "filter_mode", &mut filter.mode, &mode, |x| x.str());
+            Self::select(
+                ui,
+                "filter_enabled",
+                &mut filter.enabled,
+                &[true, false],
+                |x| {
+                    t(if *x {
+                        "filter.enabled"
+                    } else {
+                        "filter.disabled"
+                    })
+                    .into()
+                },
+            );
+            let mut inherit = filter.inherit.is_some();
+            if ui.checkbox(&mut inherit, t("filter.inherit")).changed() {
+                filter.inherit = inherit.then_some(0);
+            }
+            if let Some(x) = &mut filter.inherit {
+                ui.add(DragValue::new(x).clamp_range(0..=total - 1));
+            }
+        });
+    }
+
+    fn selection(ui: &mut egui::Ui, state: &mut Self) {
+        ui.horizontal(|ui| {
+            ui.label(t("bulk.select"));
+            let response = ui.add(
+                TextEdit::singleline(&mut state.selection_text)
+                    .hint_text("0-9 20")
+                    .desired_width(100.0),
+            );
+            if response.lost_focus() && ui.input().key_pressed(Key::Enter) {
+                match choice::parse(&state.selection_text) {
+                    Ok(choice) => state.selection.set_choice(&choice, state.account.len()),
+                    Err(e) => {
+                        state.toast.error(e);
+                    }
+                }
+            }
+            ui.label(tf("bulk.selected", &[&state.selection.len()]));
+            ui.add_enabled_ui(!state.selection.is_empty(), |ui| {
+                if ui.button(t("bulk.edit")).clicked() {
+                    state.bulk = Some(Default::default());
+                }
+                if ui.button(t("button.clear")).clicked() {
+                    state.selection.clear();
+                }
+            });
+        });
+    }
+
+    /// Editor of one scalar account field held as JSON.
+    fn value_editor(ui: &mut egui::Ui, key: &str, value: &mut serde_json::Value, total: usize) {
+        use serde_json::Value;
+        match key {
+            "server" => {
+                let mut x: Server = serde_json::from_value(value.clone()).unwrap_or_default();
+                for server in [Server::Official, Server::Bilibili] {
+                    let text = server.clone().str();
+                    ui.radio_value(&mut x, server, text);
+                }
+                *value = serde_json::to_value(x).unwrap();
+            }
+            "mode" => {
+                let mut x: AccountMode = serde_json::from_value(value.clone()).unwrap_or_default();
+                for mode in [AccountMode::Daily, AccountMode::ZL, AccountMode::Recruit] {
+                    let text = mode.str();
+                    ui.radio_value(&mut x, mode, text);
+                }
+                *value = serde_json::to_value(x).unwrap();
+            }
+            _ => match value {
+                Value::Bool(x) => {
+                    ui.checkbox(x, "");
+                }
+                Value::Number(x) => {
+                    let mut n = x.as_u64().unwrap_or(0) as usize;
+                    let range = data::range(key, total);
+                    if ui.add(DragValue::new(&mut n).clamp_range(range)).changed() {
+                        *value = n.into();
+                    }
+                }
+                Value::String(x) => {
+                    ui.text_edit_singleline(x);
+                }
+                _ => {}
+            },
+        }
+    }
+
+    fn bulk(ctx: &egui::Context, state: &mut Self) {
+        let edit = match &mut state.bulk {
+            Some(x) => x,
+            None => return,
+        };
+        let indices = state.selection.indices();
+        let total = state.account.len();
+        let mut open = true;
+        let mut apply = false;
+        egui::Window::new(t("bulk.edit"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(HEIGHT)
+                    .show(ui, |ui| {
+                        egui::Grid::new("bulk").num_columns(2).show(ui, |ui| {
+                            for key in edit.editable() {
+                                let mut checked = edit.fields.contains(key);
+                                let label = help::get(key).map_or(key, |x| x.label());
+                                let response = ui
+                                    .checkbox(&mut checked, label)
+                                    .on_hover_text(help::text(key));
+                                if response.changed() {
+                                    if checked {
+                                        edit.fields.insert(key);
+                                    } else {
+                                        edit.fields.remove(key);
+                                    }
+                                }
+                                ui.add_enabled_ui(checked, |ui| {
+                                    if let Some(value) = edit.values.get_mut(key) {
+                                        Self::value_editor(ui, key, value, total);
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                    });
+                ui.separator();
+                ui.horizontal(|ui| match edit.changes(&state.account, &indices) {
+                    Ok(changes) => {
+                        ui.label(tf("bulk.changes", &[&changes, &indices.len()]));
+                        apply = ui
+                            .add_enabled(changes > 0, Button::new(t("bulk.apply")))
+                            .clicked();
+                    }
+                    Err(e) => {
+                        ui.colored_label(Color32::RED, e);
+                    }
+                });
+            });
+        if apply {
+            match edit.apply(&mut state.account, &indices) {
+                Ok(count) => state.toast.success(tf("bulk.applied", &[&count])),
+                Err(e) => state.toast.error(e),
+            };
+        }
+        if !open || apply {
+            state.bulk = None;
+        }
+    }
+
     fn multi_account(ui: &mut egui::Ui, state: &mut Self, scroll_to_account_changed: bool) {
-        let row_height = HEIGHT;
+        let choice = choice::parse(&state.setting.multi_account_choice).unwrap_or_default();
+        state.update_usernames();
+        let usernames = &state.usernames.as_ref().unwrap().1;
+        let rows = state
+            .account_filter
+            .apply(&state.account, usernames, &choice);
+        Self::account_filter(ui, state, rows.len());
+        Self::selection(ui, state);
+        ui.separator();
+        let scroll_to = scroll_to_account_changed.then_some(state.scroll_to_account);
+        if state.account_grid {
+            Self::grid(ui, state, rows, scroll_to);
+            return;
+        }
+
+        let heights: Vec<f32> = state.card_heights.rows(&state.account, &rows).collect();
         let mut table = TableBuilder::new(ui).column(Column::initial(WIDTH));
-        if scroll_to_account_changed {
-            table = table.scroll_to_row(state.scroll_to_account, None);
+        if let Some(idx) = scroll_to {
+            table = table.scroll_to_row(filter::row_of(&rows, idx), None);
         }
 
         table.body(|body| {
-            body.rows(row_height, state.account.len(), |row_index, mut row| {
+            body.heterogeneous_rows(heights.into_iter(), |row_index, mut row| {
                 row.col(|ui| {
-                    let idx = row_index;
+                    let idx = rows[row_index];
+                    let mode = state.account[idx].mode.clone();
                     Self::one_account(ui, state, idx);
+                    // a card whose mode just changed is laid out again next frame
+                    if state.account[idx].mode == mode
+                        && state.card_heights.measure(&mode, ui.min_rect().height())
+                    {
+                        ui.ctx().request_repaint();
+                    }
                 });
             })
         });
     }
 
+    /// One row per account with the key fields editable in place.
+    fn grid(ui: &mut egui::Ui, state: &mut Self, mut rows: Vec<usize>, scroll_to: Option<usize>) {
+        if let Some((column, descending)) = state.grid_sort {
+            grid::sort(&state.account, &mut rows, column, descending);
+        }
+        let total = state.account.len();
+        let row_height = ui.spacing().interact_size.y;
+        let mut table = TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right(Align::Center))
+            .column(Column::auto())
+            .column(Column::initial(120.0).resizable(true))
+            .columns(Column::auto(), 3)
+            .column(Column::initial(160.0).resizable(true))
+            .columns(Column::auto(), 3);
+        if let Some(idx) = scroll_to {
+            table = table.scroll_to_row(filter::row_of(&rows, idx), None);
+        }
+
+        table
+            .header(row_height, |mut header| {
+                for column in grid::Column::ALL {
+                    header.col(|ui| {
+                        let mut text = column.str().to_string();
+                        match state.grid_sort {
+                            Some((x, false)) if x == column => text += " ▲",
+                            Some((x, true)) if x == column => text += " ▼",
+                            _ => {}
+                        }
+                        let response = ui.button(text).on_hover_text(help::text(column.key()));
+                        if response.clicked() {
+                            state.grid_sort = match state.grid_sort {
+                                Some((x, false)) if x == column => Some((column, true)),
+                                Some((x, true)) if x == column => None,
+                                _ => Some((column, false)),
+                            };
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(row_height, rows.len(), |row_index, mut row| {
+                    let idx = rows[row_index];
+                    let diagnostics = state.account_diagnostics(idx);
+                    let account = &mut state.account[idx];
+                    let own = account.mode != AccountMode::Daily || !account.inherit;
+                    row.col(|ui| {
+                        let response = ui
+                            .selectable_label(state.selection.contains(idx), idx.to_string())
+                            .on_hover_text(t("bulk.toggle"));
+                        if response.clicked() {
+                            let shift = ui.input().modifiers.shift;
+                            state.selection.click(idx, shift);
+                        }
+                    });
+                    row.col(|ui| {
+                        let response = ui.add(
+                            TextEdit::singleline(&mut account.username)
+                                .password(state.hide_secrets)
+                                .desired_width(f32::INFINITY),
+                        );
+                        Self::mark(ui, response, &diagnostics, "username");
+                    });
+                    row.col(|ui| {
+                        if ui.button(account.server.clone().str()).clicked() {
+                            account.server = account.server.clone().next();
+                        }
+                    });
+                    row.col(|ui| {
+                        if ui.button(account.mode.str()).clicked() {
+                            account.mode = account.mode.next();
+                        }
+                    });
+                    row.col(|ui| {
+                        if account.mode == AccountMode::Daily {
+                            ui.checkbox(&mut account.inherit, "");
+                            let response = ui.add_enabled(
+                                account.inherit,
+                                DragValue::new(&mut account.inherit_index)
+                                    .clamp_range(data::range("inherit_index", total)),
+                            );
+                            Self::mark(ui, response, &diagnostics, "inherit_index");
+                        }
+                    });
+                    row.col(|ui| {
+                        let response = ui.add_enabled(
+                            own,
+                            TextEdit::singleline(&mut account.fight).desired_width(f32::INFINITY),
+                        );
+                        Self::mark(ui, response, &diagnostics, "fight");
+                    });
+                    row.col(|ui| {
+                        ui.add_enabled(
+                            own,
+                            DragValue::new(&mut account.max_drug)
+                                .clamp_range(data::range("max_drug", total)),
+                        );
+                    });
+                    row.col(|ui| {
+                        ui.add_enabled(
+                            own,
+                            DragValue::new(&mut account.max_stone)
+                                .clamp_range(data::range("max_stone", total)),
+                        );
+                    });
+                    row.col(|ui| {
+                        ui.add_enabled_ui(own, |ui| {
+                            ui.spacing_mut().item_spacing.x = 0.0;
+                            for (i, day) in account.weekdays_mut().into_iter().enumerate() {
+                                ui.checkbox(day, "")
+                                    .on_hover_text(t(schedule::WEEKDAY[i]))
+                                    .on_disabled_hover_text(t(schedule::WEEKDAY[i]));
+                            }
+                        });
+                    });
+                })
+            });
+    }
+
     fn setting(ui: &mut egui::Ui, state: &mut Self) {
+        let diagnostics = validate::setting(&state.setting, state.account.len());
+        if ui.button(t("json.edit")).clicked() {
+            state.open_json(json::Scope::Setting);
+        }
         ui.horizontal(|ui| {
-            ui.label("图鉴账号");
-            ui.text_edit_singleline(&mut state.setting.captcha_username);
+            Self::label(ui, state, t("setting.captcha_username"), "captcha_username");
+            ui.add(
+                TextEdit::singleline(&mut state.setting.captcha_username)
+                    .password(state.hide_secrets),
+            );
         });
         ui.horizontal(|ui| {
-            ui.label("图鉴密码");
-            ui.text_edit_singleline(&mut state.setting.captcha_password);
+            Self::label(ui, state, t("setting.captcha_password"), "captcha_password");
+            let hide = state.hide_secrets;
+            let text = &mut state.setting.captcha_password;
+            let response = Self::secret(ui, hide, text, "captcha_password");
+            Self::mark(ui, response, &diagnostics, "captcha_password");
         });
         ui.horizontal(|ui| {
-            ui.label("同一关卡连续导航或代理失败出现");
+            Self::label(
+                ui,
+                state,
+                t("setting.max_fight_failed"),
+                "max_fight_failed_times",
+            );
             ui.add(
                 DragValue::new(&mut state.setting.max_fight_failed_times)
-                    .suffix("次")
+                    .suffix(t("unit.times"))
                     .clamp_range(0..=99),
             );
-            ui.label("后跳过");
+            ui.label(t("setting.max_fight_failed_after"));
         });
         ui.horizontal(|ui| {
-            ui.label("同一账号登录界面15分钟内出现");
+            Self::label(ui, state, t("setting.max_login"), "max_login_times_15min");
             ui.add(
                 DragValue::new(&mut state.setting.max_login_times_15min)
-                    .suffix("次")
+                    .suffix(t("unit.times"))
                     .clamp_range(0..=99),
             );
-            ui.label("后跳过");
+            ui.label(t("setting.max_login_after"));
         });
         // ui.horizontal(|ui| {
         //     ui.label("同一账号6至0天理智药分别吃");
@@ -491,27 +1490,44 @@ impl MyApp {
         //     ui.label("个")
         // });
         ui.horizontal(|ui| {
-            ui.label("通知账号");
-            ui.text_edit_singleline(&mut state.setting.qq_notify);
+            Self::label(ui, state, t("setting.qq_notify"), "qq_notify");
+            let hide = state.hide_secrets;
+            let text = &mut state.setting.qq_notify;
+            let response = Self::secret(ui, hide, text, "qq_notify");
+            Self::mark(ui, response, &diagnostics, "qq_notify");
         });
         ui.horizontal(|ui| {
-            ui.label("通知服务");
-            ui.text_edit_singleline(&mut state.setting.qq_notify_server);
+            Self::label(ui, state, t("setting.qq_notify_server"), "qq_notify_server");
+            let hide = state.hide_secrets;
+            let text = &mut state.setting.qq_notify_server;
+            let response = Self::secret(ui, hide, text, "qq_notify_server");
+            Self::mark(ui, response, &diagnostics, "qq_notify_server");
         });
         ui.horizontal(|ui| {
-            ui.label("通知场景");
+            ui.label(t("setting.notify_scene"));
             // egui::Grid::new("qq_notify_scene").show(ui, |ui| {
-            ui.checkbox(&mut state.setting.qq_notify_mail, "邮件前");
-            ui.checkbox(&mut state.setting.qq_notify_dorm_enter, "进基建");
-            ui.checkbox(&mut state.setting.qq_notify_dorm_leave, "出基建");
-            ui.checkbox(&mut state.setting.qq_notify_task, "任务前");
+            ui.checkbox(&mut state.setting.qq_notify_mail, t("notify.mail"))
+                .on_hover_text(help::text("qq_notify_mail"));
+            ui.checkbox(
+                &mut state.setting.qq_notify_dorm_enter,
+                t("notify.dorm_enter"),
+            )
+            .on_hover_text(help::text("qq_notify_dorm_enter"));
+            ui.checkbox(
+                &mut state.setting.qq_notify_dorm_leave,
+                t("notify.dorm_leave"),
+            )
+            .on_hover_text(help::text("qq_notify_dorm_leave"));
+            ui.checkbox(&mut state.setting.qq_notify_task, t("notify.task"))
+                .on_hover_text(help::text("qq_notify_task"));
             // });
             // ui.checkbox(checked, text)
             // ui.text_edit_singleline(&mut state.setting.captcha_password);
         });
         ui.horizontal(|ui| {
-            ui.label("多号模式");
-            ui.checkbox(&mut state.setting.multi_account, "");
+            Self::label(ui, state, t("setting.multi_account"), "multi_account");
+            ui.checkbox(&mut state.setting.multi_account, "")
+                .on_hover_text(help::text("multi_account"));
         });
 
         // let txt = if self.multi_account {
@@ -523,44 +1539,1044 @@ impl MyApp {
         //     self.multi_account = !self.multi_account;
         // }
         ui.horizontal(|ui| {
-            ui.label("多号线索账号");
-            ui.text_edit_singleline(&mut state.setting.multi_account_clue);
+            Self::label(
+                ui,
+                state,
+                t("setting.multi_account_clue"),
+                "multi_account_clue",
+            );
+            let response = ui.text_edit_singleline(&mut state.setting.multi_account_clue);
+            Self::mark(ui, response, &diagnostics, "multi_account_clue");
         });
         ui.horizontal(|ui| {
-            ui.label("多号不跳过空白账号");
-            ui.checkbox(&mut state.setting.multi_account_allow_empty, "");
+            Self::label(
+                ui,
+                state,
+                t("setting.multi_account_allow_empty"),
+                "multi_account_allow_empty",
+            );
+            ui.checkbox(&mut state.setting.multi_account_allow_empty, "")
+                .on_hover_text(help::text("multi_account_allow_empty"));
         });
 
         ui.horizontal(|ui| {
-            ui.label("定时重启");
-            ui.text_edit_singleline(&mut state.setting.crontab)
+            Self::label(ui, state, t("setting.crontab"), "crontab");
+            let response = ui.text_edit_singleline(&mut state.setting.crontab);
+            Self::mark(ui, response, &diagnostics, "crontab");
+        });
+        ui.horizontal(|ui| {
+            Self::label(ui, state, t("setting.runner_url"), "runner_url");
+            let response = ui.text_edit_singleline(&mut state.setting.runner_url);
+            Self::mark(ui, response, &diagnostics, "runner_url");
+        });
+        ui.horizontal(|ui| {
+            Self::label(ui, state, t("setting.language"), "language");
+            for lang in Lang::ALL {
+                ui.radio_value(&mut state.setting.language, lang, lang.name());
+            }
         });
     }
-}
 
-impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-
-        self.toast.show(ctx);
-
-        egui::TopBottomPanel::top("top").show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
-                ui.label("Mizuki 611-12.01");
-            });
+    fn log(ui: &mut egui::Ui, state: &mut Self) {
+        ui.horizontal(|ui| {
+            ui.label(t("log.account"));
+            ui.add(TextEdit::singleline(&mut state.log_filter.account).desired_width(48.0));
+            egui::ComboBox::from_id_source("log_level")
+                .selected_text(state.log_filter.level.str())
+                .show_ui(ui, |ui| {
+                    for level in LogLevel::ALL {
+                        ui.selectable_value(&mut state.log_filter.level, level, level.str());
+                    }
+                });
+            ui.label(t("search"));
+            ui.text_edit_singleline(&mut state.log_filter.search);
+            ui.checkbox(&mut state.log_follow, t("log.follow"));
+            if ui.button(t("button.export")).clicked() {
+                // secrets entered after a line was logged are masked too
+                let redactor = Redactor::new(&state.account, &state.setting);
+                let text = redactor.text(&state.log.export(&state.log_filter));
+                match file::save_text("mizuki_log.txt", &text) {
+                    Ok(path) => state.toast.success(tf("toast.exported", &[&path])),
+                    Err(e) => state.toast.error(e),
+                };
+            }
+            if ui
+                .button(t("log.issue_report"))
+                .on_hover_text(t("log.issue_report_hint"))
+                .clicked()
+            {
+                let text = redact::issue_report(&state.account, &state.setting, &state.log);
+                match file::save_text("mizuki_issue.txt", &text) {
+                    Ok(path) => state.toast.success(tf("toast.exported", &[&path])),
+                    Err(e) => state.toast.error(e),
+                };
+            }
+            if ui.button(t("button.clear")).clicked() {
+                state.log.clear();
+            }
         });
 
-        let mut scroll_to_account_changed = false;
-        egui::TopBottomPanel::bottom("bottom").show(ctx, |ui| {
+        ui.collapsing(t("log.import_reports"), |ui| {
+            ui.add(
+                TextEdit::multiline(&mut state.report_text)
+                    .code_editor()
+                    .desired_rows(4)
+                    .desired_width(f32::INFINITY),
+            );
+            if ui.button(t("button.import")).clicked() {
+                match report::parse(&state.report_text) {
+                    Ok(reports) => {
+                        state
+                            .toast
+                            .success(tf("toast.imported_reports", &[&reports.len()]));
+                        for report in reports {
+                            state.history.add(report);
+                        }
+                        state.report_text.clear();
+                    }
+                    Err(e) => {
+                        state.toast.error(e);
+                    }
+                }
+            }
+        });
+
+        let lines = state.log.filtered(&state.log_filter);
+        ui.label(format!("{}/{}", lines.len(), state.log.len()));
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both()
+            .auto_shrink([false; 2])
+            .stick_to_bottom(state.log_follow)
+            .show_rows(ui, row_height, lines.len(), |ui, range| {
+                for i in range {
+                    let line = match state.log.get(lines[i]) {
+                        Some(line) => line,
+                        None => continue,
+                    };
+                    let text = egui::RichText::new(line.to_text()).monospace();
+                    let text = match line.level {
+                        LogLevel::Debug => text.weak(),
+                        LogLevel::Info => text,
+                        LogLevel::Warn => text.color(Color32::YELLOW),
+                        LogLevel::Error => text.color(Color32::RED),
+                    };
+                    ui.add(egui::Label::new(text).wrap(false));
+                }
+            });
+    }
+
+    fn schedule(ui: &mut egui::Ui, state: &mut Self) {
+        ui.horizontal(|ui| {
+            ui.label(t("schedule.title"));
+            if state.schedule.is_none() || ui.button(t("button.refresh")).clicked() {
+                let now = Local::now().naive_local();
+                state.schedule = Some(schedule::ticks(&state.account, &state.setting, now, 7));
+            }
+        });
+        let ticks = match &state.schedule {
+            Some(Ok(ticks)) => ticks,
+            Some(Err(e)) => {
+                ui.colored_label(Color32::RED, e);
+                return;
+            }
+            None => return,
+        };
+        if ticks.is_empty() {
+            ui.label(t("schedule.empty"));
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let mut last_date = None;
+            for tick in ticks {
+                let date = tick.time.date();
+                if last_date != Some(date) {
+                    last_date = Some(date);
+                    let weekday =
+                        t(schedule::WEEKDAY[date.weekday().num_days_from_monday() as usize]);
+                    ui.separator();
+                    ui.strong(format!("{} {}", date.format("%m-%d"), weekday));
+                }
+                ui.horizontal_wrapped(|ui| {
+                    ui.monospace(tick.time.format("%H:%M").to_string());
+                    ui.label(tf("schedule.count", &[&tick.account.len()]));
+                    let mut text = choice::format(&tick.account);
+                    if text.chars().count() > 60 {
+                        text = text.chars().take(57).collect::<String>() + "...";
+                    }
+                    ui.label(RichText::new(text).weak());
+                });
+            }
+        });
+    }
+
+    fn transfer(ui: &mut egui::Ui, state: &mut Self) {
+        Self::config_file(ui, state);
+        ui.separator();
+        Self::share(ui, state);
+        ui.separator();
+        Self::encryption(ui, state);
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(t("transfer.title"));
+            for format in TransferFormat::ALL {
+                if ui
+                    .radio_value(&mut state.transfer_format, format, format.str())
+                    .changed()
+                {
+                    state.transfer_text.clear();
+                    state.csv_import = None;
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button(t("button.export")).clicked() {
+                let (name, text) = state.transfer_export();
+                match file::save_text(name, &text) {
+                    Ok(path) => state.toast.success(tf("toast.exported", &[&path])),
+                    Err(e) => state.toast.error(e),
+                };
+                state.transfer_text = text;
+            }
+            if ui.button(t("qr.button")).clicked() {
+                let (_, text) = state.transfer_export();
+                state.open_qr(&text);
+            }
+            if ui.button(t("button.import")).clicked() {
+                let text = &state.transfer_text;
+                match state.transfer_format {
+                    TransferFormat::Script => match config::decode(text, state.account.len()) {
+                        Ok(mut config) => {
+                            share::keep_local(&mut config.setting, &state.setting);
+                            state.account = config.account;
+                            state.setting = config.setting;
+                            state.toast.success(t("toast.imported_config"));
+                            state.decrypt_loaded();
+                        }
+                        Err(e) => {
+                            state.toast.error(e);
+                        }
+                    },
+                    TransferFormat::Csv => match csv::import(text, &state.account) {
+                        Ok(import) => state.csv_import = Some(import),
+                        Err(e) => {
+                            state.toast.error(e);
+                        }
+                    },
+                }
+            }
+            if ui.button(t("button.clear")).clicked() {
+                state.transfer_text.clear();
+                state.csv_import = None;
+            }
+            if ui.button(t("json.edit_all")).clicked() {
+                state.open_json(json::Scope::All);
+            }
+        });
+        if state.csv_import.is_some() {
+            Self::csv_preview(ui, state);
+            return;
+        }
+        let hint = match state.transfer_format {
+            TransferFormat::Script => t("transfer.paste"),
+            TransferFormat::Csv => t("csv.paste"),
+        };
+        ui.label(RichText::new(hint).weak());
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add(
+                TextEdit::multiline(&mut state.transfer_text)
+                    .code_editor()
+                    .desired_rows(20)
+                    .desired_width(f32::INFINITY),
+            );
+        });
+    }
+
+    /// The file name and text of an export in the chosen format.
+    fn transfer_export(&self) -> (&'static str, String) {
+        let (account, setting) = self.exported();
+        match self.transfer_format {
+            TransferFormat::Script => {
+                let config = Config { account, setting };
+                ("mizuki_config.txt", config::encode(&config))
+            }
+            TransferFormat::Csv => ("mizuki_accounts.csv", csv::export(&account)),
+        }
+    }
+
+    /// The config as written out: with secrets encrypted while a passphrase
+    /// is set, unless the user chose to export them decrypted.
+    fn exported(&self) -> (Vec<Account>, Setting) {
+        let mut account = self.account.clone();
+        let mut setting = self.setting.clone();
+        if let Some(vault) = &self.vault {
+            if !self.export_plain {
+                vault::encrypt_all(vault, &mut account, &mut setting);
+            }
+        }
+        (account, setting)
+    }
+
+    /// Decrypts secrets just loaded with the passphrase already entered, and
+    /// asks for it again when that does not fit.
+    fn decrypt_loaded(&mut self) {
+        self.unlock_later = false;
+        if let Some(vault) = &self.vault {
+            if !vault::locked(&self.account, &self.setting).is_empty() {
+                let _ = vault::decrypt_all(vault, &mut self.account, &mut self.setting);
+            }
+        }
+    }
+
+    fn encryption(ui: &mut egui::Ui, state: &mut Self) {
+        if state.vault.is_some() {
+            ui.label(t("vault.enabled"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.export_plain, t("vault.export_plain"));
+                if ui.button(t("vault.disable")).clicked() {
+                    state.vault = None;
+                    state.export_plain = false;
+                }
+            });
+            return;
+        }
+        ui.label(RichText::new(t("vault.hint")).weak());
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut state.vault_pass)
+                    .password(true)
+                    .hint_text(t("vault.passphrase")),
+            );
+            ui.add(
+                TextEdit::singleline(&mut state.vault_confirm)
+                    .password(true)
+                    .hint_text(t("vault.confirm")),
+            );
+            let ready = !state.vault_pass.is_empty()
+                && state.vault_pass == state.vault_confirm
+                && state.vault_deriving.is_none();
+            if ui
+                .add_enabled(ready, Button::new(t("vault.enable")))
+                .on_disabled_hover_text(t("vault.mismatch"))
+                .clicked()
+            {
+                let pass = std::mem::take(&mut state.vault_pass);
+                state.vault_deriving = Some((false, vault::new_later(pass)));
+                state.vault_confirm.clear();
+            }
+            if state.vault_deriving.is_some() {
+                ui.spinner();
+            }
+        });
+    }
+
+    /// Asks for the passphrase while the config holds encrypted secrets.
+    fn unlock(ctx: &egui::Context, state: &mut Self) {
+        if state.unlock_later {
+            return;
+        }
+        let samples = vault::locked(&state.account, &state.setting);
+        if samples.is_empty() {
+            return;
+        }
+        let mut unlock = false;
+        egui::Window::new(t("vault.unlock"))
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t("vault.locked"));
+                let response = ui.add(
+                    TextEdit::singleline(&mut state.vault_pass)
+                        .password(true)
+                        .hint_text(t("vault.passphrase")),
+                );
+                unlock = response.lost_focus() && ui.input().key_pressed(Key::Enter);
+                ui.horizontal(|ui| {
+                    if state.vault_deriving.is_some() {
+                        ui.spinner();
+                        return;
+                    }
+                    unlock |= ui.button(t("vault.unlock")).clicked();
+                    state.unlock_later = ui.button(t("vault.later")).clicked();
+                });
+            });
+        if unlock && state.vault_deriving.is_none() {
+            let pass = std::mem::take(&mut state.vault_pass);
+            state.vault_deriving = Some((true, vault::unlock_later(pass, samples)));
+        }
+    }
+
+    /// Takes the vault once its keys are derived, decrypting the config
+    /// with it when it was unlocking.
+    fn poll_vault(&mut self, ctx: &egui::Context) {
+        let Some((unlocking, promise)) = self.vault_deriving.take() else {
+            return;
+        };
+        let result = match promise.try_take() {
+            Ok(x) => x,
+            Err(promise) => {
+                self.vault_deriving = Some((unlocking, promise));
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                return;
+            }
+        };
+        let derived = result.and_then(|vault| {
+            if unlocking {
+                vault::decrypt_all(&vault, &mut self.account, &mut self.setting)?;
+                self.config_changed = true;
+                self.toast.success(t("toast.unlocked"));
+            }
+            Ok(vault)
+        });
+        match derived {
+            Ok(vault) => self.vault = Some(vault),
+            Err(e) => {
+                self.toast.error(e);
+            }
+        }
+    }
+
+    fn open_qr(&mut self, text: &str) {
+        let codes = qr::chunks(text, qr::CHUNK)
+            .iter()
+            .map(|x| qr::Code::new(x))
+            .collect::<Result<Vec<_>, _>>();
+        match codes {
+            Ok(codes) => self.qr = Some(QrView { codes, page: 0 }),
+            Err(e) => {
+                self.toast.error(e);
+            }
+        }
+    }
+
+    fn qr(ctx: &egui::Context, state: &mut Self) {
+        let Some(view) = &mut state.qr else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(t("qr.title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let total = view.codes.len();
+                if total > 1 {
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(view.page > 0, Button::new("◀")).clicked() {
+                            view.page -= 1;
+                        }
+                        ui.label(tf("qr.page", &[&(view.page + 1), &total]));
+                        if ui
+                            .add_enabled(view.page + 1 < total, Button::new("▶"))
+                            .clicked()
+                        {
+                            view.page += 1;
+                        }
+                    });
+                }
+                let code = &view.codes[view.page];
+                // the quiet zone scanners need around the code
+                let quiet = 4;
+                let module = HEIGHT / (code.width + 2 * quiet) as f32;
+                let (rect, _) =
+                    ui.allocate_exact_size(egui::vec2(HEIGHT, HEIGHT), egui::Sense::hover());
+                let painter = ui.painter_at(rect);
+                painter.rect_filled(rect, 0.0, Color32::WHITE);
+                for y in 0..code.width {
+                    for x in 0..code.width {
+                        if code.is_dark(x, y) {
+                            let min = rect.min
+                                + egui::vec2((x + quiet) as f32, (y + quiet) as f32) * module;
+                            let square = egui::Rect::from_min_size(min, egui::vec2(module, module));
+                            painter.rect_filled(square, 0.0, Color32::BLACK);
+                        }
+                    }
+                }
+                if total > 1 {
+                    ui.label(RichText::new(t("qr.hint")).weak());
+                }
+            });
+        if !open {
+            state.qr = None;
+        }
+    }
+
+    /// What a CSV import will write, before it is applied.
+    fn csv_preview(ui: &mut egui::Ui, state: &mut Self) {
+        let Some(import) = &state.csv_import else {
+            return;
+        };
+        let overwrite = import.rows.iter().filter(|x| x.overwrite).count();
+        ui.separator();
+        ui.label(tf("csv.preview", &[&import.rows.len(), &overwrite]));
+        let mut close = false;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!import.rows.is_empty(), Button::new(t("csv.apply")))
+                .clicked()
+            {
+                import.apply(&mut state.account);
+                state
+                    .toast
+                    .success(tf("toast.imported_accounts", &[&import.rows.len()]));
+                close = true;
+            }
+            if ui.button(t("csv.discard")).clicked() {
+                close = true;
+            }
+        });
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for e in &import.errors {
+                ui.colored_label(Color32::RED, e);
+            }
+            for row in &import.rows {
+                ui.horizontal_wrapped(|ui| {
+                    ui.monospace(format!("#{}", row.idx));
+                    ui.label(Self::masked(&row.account.username, state.hide_secrets));
+                    if row.overwrite {
+                        ui.colored_label(Color32::YELLOW, t("csv.overwrite"));
+                    }
+                    let changed = if row.changed.is_empty() {
+                        t("csv.unchanged").to_string()
+                    } else {
+                        row.changed
+                            .iter()
+                            .map(|x| help::get(x).map_or(*x, |x| x.label()))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    };
+                    ui.label(RichText::new(changed).weak());
+                });
+            }
+        });
+        if close {
+            state.csv_import = None;
+            state.decrypt_loaded();
+        }
+    }
+
+    /// Saves the whole config as JSON, to the current file unless `path` is given.
+    fn save_config(&mut self, path: Option<String>) {
+        let (account, setting) = self.exported();
+        let document = json::document(json::Scope::All, &account, &setting);
+        let text = serde_json::to_string_pretty(&document).unwrap();
+        let path = path.unwrap_or_else(|| self.config_path.clone());
+        match file::save_as(&path, &text) {
+            Ok(saved) => {
+                self.toast.success(tf("toast.saved", &[&saved]));
+                self.config_path = path;
+            }
+            Err(e) => {
+                self.toast.error(e);
+            }
+        };
+    }
+
+    /// Starts reading a config file, applied by [`Self::poll_config`] once read.
+    pub fn open_config(&mut self, path: &str) {
+        self.config_opening = Some(file::open(path));
+    }
+
+    fn poll_config(&mut self) {
+        let result = match &self.config_opening {
+            Some(promise) => match promise.ready() {
+                Some(x) => x.clone(),
+                None => return,
+            },
+            None => return,
+        };
+        self.config_opening = None;
+        let Some(result) = result else {
+            return;
+        };
+        let opened = result.and_then(|(path, text)| {
+            let document = json::parse(json::Scope::All, &text, self.account.len())?;
+            Ok((path, document))
+        });
+        match opened {
+            Ok((path, document)) => {
+                let scope = json::Scope::All;
+                json::apply(scope, &document, &mut self.account, &mut self.setting);
+                self.config_changed = true;
+                self.toast.success(tf("toast.opened", &[&path]));
+                self.config_path = path;
+                self.decrypt_loaded();
+            }
+            Err(e) => {
+                self.toast.error(e);
+            }
+        }
+    }
+
+    fn config_file(ui: &mut egui::Ui, state: &mut Self) {
+        ui.horizontal(|ui| {
+            ui.label(t("file.current"));
+            ui.monospace(&state.config_path);
+        });
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut state.config_path_edit).hint_text(t("file.path")));
+            let opening = state.config_opening.is_some();
+            if ui
+                .add_enabled(!opening, Button::new(t("file.open")))
+                .clicked()
+            {
+                let path = state.config_path_edit.clone();
+                state.open_config(&path);
+            }
+            if ui.button(t("file.save")).clicked() {
+                state.save_config(None);
+            }
+            let path = state.config_path_edit.trim().to_string();
+            let save_as = Button::new(t("file.save_as"));
+            if ui.add_enabled(!path.is_empty(), save_as).clicked() {
+                state.save_config(Some(path));
+            }
+        });
+    }
+
+    /// Moves to the account `offset` away from the current one and focuses it.
+    fn step_account(&mut self, offset: isize) {
+        if !self.setting.multi_account {
+            return;
+        }
+        let last = self.account.len() as isize - 1;
+        let idx = (self.scroll_to_account as isize + offset).clamp(0, last) as usize;
+        self.layout = Layout::Account;
+        self.scroll_to_account = idx;
+        self.scroll_pending = true;
+        self.focus_account = Some(idx);
+    }
+
+    fn shortcut(&mut self, action: Action) {
+        match action {
+            Action::NextAccount => self.step_account(1),
+            Action::PrevAccount => self.step_account(-1),
+            Action::Jump => {
+                if self.setting.multi_account {
+                    self.jump = Some(String::new());
+                }
+            }
+            Action::ToggleSetting => self.layout = self.layout.toggle_default(Layout::Setting),
+            Action::Save => self.save_config(None),
+            Action::Undo => {
+                self.undo.undo(&mut self.account, &mut self.setting);
+            }
+            Action::Redo => {
+                self.undo.redo(&mut self.account, &mut self.setting);
+            }
+            Action::CheatSheet => self.shortcuts_open = !self.shortcuts_open,
+        }
+    }
+
+    /// The values an edit changes, old in red and new in green.
+    fn changes(ui: &mut egui::Ui, id: &str, changes: &[json::Change]) {
+        ui.label(tf("json.changes", &[&changes.len()]));
+        egui::ScrollArea::vertical()
+            .id_source(id)
+            .max_height(HEIGHT / 2.0)
+            .show(ui, |ui| {
+                egui::Grid::new(id).show(ui, |ui| {
+                    for change in changes {
+                        ui.monospace(&change.path);
+                        ui.colored_label(Color32::RED, &change.before);
+                        ui.colored_label(Color32::GREEN, &change.after);
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    fn share(ui: &mut egui::Ui, state: &mut Self) {
+        ui.horizontal(|ui| {
+            if ui.button(t("share.create")).clicked() {
+                let code = share::encode(&state.account, &state.setting);
+                state.share_code = match share::base_url() {
+                    Some(base) => share::link(&base, &code),
+                    None => code,
+                };
+            }
+            ui.add_enabled_ui(!state.share_code.is_empty(), |ui| {
+                if ui.button(t("share.copy")).clicked() {
+                    ui.output().copied_text = state.share_code.clone();
+                }
+            });
+            let mut code = state.share_code.as_str();
+            ui.add(TextEdit::singleline(&mut code).desired_width(f32::INFINITY));
+        });
+        ui.horizontal(|ui| {
+            if ui.button(t("share.preview")).clicked() {
+                let text = std::mem::take(&mut state.share_text);
+                state.preview_share(&text);
+            }
+            ui.add(
+                TextEdit::singleline(&mut state.share_text)
+                    .hint_text(t("share.paste"))
+                    .desired_width(f32::INFINITY),
+            );
+        });
+    }
+
+    /// Opens the preview of a share code or link, applied once confirmed.
+    fn preview_share(&mut self, text: &str) {
+        match share::decode(text, self.account.len()) {
+            Ok(document) => {
+                let revision = self.undo.revision();
+                let import = ShareImport::new(document, &self.account, &self.setting, revision);
+                self.share_import = Some(import);
+            }
+            Err(e) => {
+                self.toast.error(e);
+            }
+        }
+    }
+
+    fn share_import(ctx: &egui::Context, state: &mut Self) {
+        let revision = state.undo.revision();
+        let Some(import) = &mut state.share_import else {
+            return;
+        };
+        if import.revision != revision {
+            let document = std::mem::take(&mut import.document);
+            *import = ShareImport::new(document, &state.account, &state.setting, revision);
+        }
+        let mut open = true;
+        let mut apply = false;
+        let mut discard = false;
+        egui::Window::new(t("share.title"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t("share.keeps_credentials"));
+                if import.changes.is_empty() {
+                    ui.label(t("json.no_changes"));
+                } else {
+                    Self::changes(ui, "share_changes", &import.changes);
+                }
+                ui.horizontal(|ui| {
+                    let ready = !import.changes.is_empty();
+                    apply = ui
+                        .add_enabled(ready, Button::new(t("json.apply")))
+                        .clicked();
+                    discard = ui.button(t("csv.discard")).clicked();
+                });
+            });
+        if apply {
+            if let Some(import) = state.share_import.take() {
+                state.account = import.account;
+                state.setting = import.setting;
+                state.toast.success(t("toast.imported_config"));
+            }
+        } else if !open || discard {
+            state.share_import = None;
+        }
+    }
+
+    fn open_json(&mut self, scope: json::Scope) {
+        let document = json::document(scope, &self.account, &self.setting);
+        self.json_edit = Some(JsonEdit {
+            scope,
+            text: serde_json::to_string_pretty(&document).unwrap(),
+            parsed: Ok(document),
+            changes: vec![],
+            revision: self.undo.revision(),
+        });
+    }
+
+    fn json_editor(ctx: &egui::Context, state: &mut Self) {
+        let Some(edit) = &mut state.json_edit else {
+            return;
+        };
+        let mut open = true;
+        let mut apply = false;
+        let mut reload = false;
+        egui::Window::new(tf("json.title", &[&edit.scope.str()]))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(RichText::new(t("json.hint")).weak());
+                let response = egui::ScrollArea::vertical()
+                    .id_source("json_text")
+                    .max_height(HEIGHT)
+                    .show(ui, |ui| {
+                        ui.add(
+                            TextEdit::multiline(&mut edit.text)
+                                .code_editor()
+                                .desired_width(f32::INFINITY),
+                        )
+                    })
+                    .inner;
+                if response.changed() {
+                    let total = state.account.len();
+                    edit.parsed = json::parse(edit.scope, &edit.text, total);
+                }
+                let revision = state.undo.revision();
+                if response.changed() || edit.revision != revision {
+                    edit.revision = revision;
+                    edit.changes = match &edit.parsed {
+                        Ok(after) => {
+                            let before = json::document(edit.scope, &state.account, &state.setting);
+                            json::diff(edit.scope, &before, after)
+                        }
+                        Err(_) => vec![],
+                    };
+                }
+                match &edit.parsed {
+                    Err(e) => {
+                        ui.colored_label(Color32::RED, e);
+                    }
+                    Ok(_) if edit.changes.is_empty() => {
+                        ui.label(t("json.no_changes"));
+                    }
+                    Ok(_) => Self::changes(ui, "json_changes", &edit.changes),
+                }
+                ui.horizontal(|ui| {
+                    let ready = edit.parsed.is_ok() && !edit.changes.is_empty();
+                    apply = ui
+                        .add_enabled(ready, Button::new(t("json.apply")))
+                        .clicked();
+                    reload = ui.button(t("json.reload")).clicked();
+                });
+            });
+        let scope = edit.scope;
+        if apply {
+            if let Ok(document) = &edit.parsed {
+                json::apply(scope, document, &mut state.account, &mut state.setting);
+            }
+        }
+        if !open {
+            state.json_edit = None;
+        } else if apply || reload {
+            state.open_json(scope);
+        }
+    }
+
+    fn jump(ctx: &egui::Context, state: &mut Self) {
+        let text = match &mut state.jump {
+            Some(x) => x,
+            None => return,
+        };
+        let mut open = true;
+        let mut target = None;
+        egui::Window::new(t("shortcut.jump"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let response = ui.add(TextEdit::singleline(text).hint_text("0-9999"));
+                response.request_focus();
+                if ui.input().key_pressed(Key::Enter) {
+                    target = Some(text.trim().parse::<usize>());
+                }
+            });
+        if ctx.input().key_pressed(Key::Escape) {
+            open = false;
+        }
+        match target {
+            Some(Ok(idx)) => {
+                state.jump = None;
+                state.scroll_to_account = idx.min(state.account.len() - 1);
+                state.step_account(0);
+                ctx.request_repaint();
+            }
+            Some(Err(_)) => {
+                state.toast.error(t("shortcut.bad_index"));
+            }
+            None if !open => state.jump = None,
+            None => {}
+        }
+    }
+
+    fn shortcuts(ctx: &egui::Context, state: &mut Self) {
+        egui::Window::new(t("shortcut.cheat_sheet"))
+            .open(&mut state.shortcuts_open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts").striped(true).show(ui, |ui| {
+                    for (action, shortcut) in shortcut::SHORTCUTS {
+                        ui.monospace(ctx.format_shortcut(shortcut));
+                        ui.label(action.str());
+                        ui.end_row();
+                    }
+                    ui.monospace("Tab / Shift+Tab");
+                    ui.label(t("shortcut.focus"));
+                    ui.end_row();
+                });
+            });
+    }
+
+    /// Problems found when starting a run, each linking to its field and help.
+    fn problems(ctx: &egui::Context, state: &mut Self) {
+        if state.problems.is_none() {
+            return;
+        }
+        // kept up to date while the user fixes them
+        let problems = validate::all(&state.account, &state.setting);
+        let mut open = true;
+        let mut start = false;
+        egui::Window::new(t("problems.title"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(HEIGHT)
+                    .show(ui, |ui| {
+                        egui::Grid::new("problems").striped(true).show(ui, |ui| {
+                            for problem in &problems {
+                                let target = match problem.target {
+                                    Target::Account(idx) => format!("#{idx}"),
+                                    Target::Setting => t("section.setting").into(),
+                                };
+                                if ui.link(target).clicked() {
+                                    match problem.target {
+                                        Target::Account(idx) => {
+                                            state.scroll_to_account = idx;
+                                            state.step_account(0);
+                                        }
+                                        Target::Setting => state.layout = Layout::Setting,
+                                    }
+                                }
+                                let label =
+                                    help::get(problem.field).map_or(problem.field, |x| x.label());
+                                if ui.link(label).clicked() {
+                                    state.show_help(problem.field);
+                                }
+                                ui.colored_label(
+                                    Self::severity_color(problem.severity),
+                                    problem.severity.str(),
+                                );
+                                ui.label(&problem.message);
+                                ui.end_row();
+                            }
+                        });
+                    });
+                ui.separator();
+                let errors = problems.iter().any(|x| x.severity == Severity::Error);
+                start = ui
+                    .add_enabled(!errors, Button::new(t("problems.start_anyway")))
+                    .clicked();
+            });
+        if start {
+            state.start_run();
+        }
+        state.problems = (open && !start).then_some(problems);
+    }
+
+    fn start_run(&mut self) {
+        let runner = HttpRunner::new(&self.setting.runner_url);
+        let request = RunRequest {
+            account: &self.account,
+            setting: &self.setting,
+        };
+        self.run.start(Box::new(runner), &request);
+    }
+
+    fn run_state(ui: &mut egui::Ui, state: &mut Self) {
+        let run_state = state.run.state();
+        if run_state.is_active() {
+            if ui.button(t("button.stop")).clicked() {
+                state.run.stop();
+            }
+        } else {
+            ui.add_enabled_ui(state.layout == Layout::default(), |ui| {
+                if ui.button(t("button.start")).clicked() {
+                    let problems = validate::all(&state.account, &state.setting);
+                    if problems.is_empty() {
+                        state.start_run();
+                    } else {
+                        state.problems = Some(problems);
+                    }
+                }
+            });
+        }
+        let run_state = state.run.state();
+        if let RunState::Error(_) = run_state {
+            ui.colored_label(Color32::RED, run_state.str());
+        } else if run_state != &RunState::Idle {
+            ui.label(run_state.str());
+        }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        i18n::set(self.setting.language);
+        self.toast.show(ctx);
+
+        for action in shortcut::pressed(&mut ctx.input_mut()) {
+            self.shortcut(action);
+        }
+        self.poll_config();
+        self.poll_vault(ctx);
+
+        if self.run.poll(ctx.input().time).is_active() {
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        }
+        let events = self.run.drain();
+        if !events.is_empty() {
+            self.update_redactor();
+            let redactor = &self.redactor.as_ref().unwrap().1;
+            for event in events {
+                match event {
+                    RunEvent::Log(mut line) => {
+                        line.text = redactor.text(&line.text);
+                        self.log.push(line);
+                    }
+                    RunEvent::Report(mut report) => {
+                        for x in &mut report.failures {
+                            *x = redactor.text(x);
+                        }
+                        self.history.add(report);
+                    }
+                }
+            }
+        }
+
+        egui::TopBottomPanel::top("top").show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.label("Mizuki 611-12.01");
+            });
+        });
+
+        let mut scroll_to_account_changed = std::mem::take(&mut self.scroll_pending);
+        egui::TopBottomPanel::bottom("bottom").show(ctx, |ui| {
             ui.vertical_centered(|ui| {
                 ui.add_sized(egui::vec2(WIDTH, 0.0), |ui: &mut egui::Ui| {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         ui.horizontal(|ui| {
-                            ui.add_enabled_ui(self.layout == Layout::default(), |ui| {
-                                if ui.button("启动").clicked() {}
-                            });
-                            if ui.button("设置").clicked() {
+                            Self::run_state(ui, self);
+                            ui.toggle_value(&mut self.hide_secrets, "🔒")
+                                .on_hover_text(t("secret.hide_all"));
+                            let redo = Button::new("↷");
+                            if ui
+                                .add_enabled(self.undo.can_redo(), redo)
+                                .on_hover_text(t("shortcut.redo"))
+                                .clicked()
+                            {
+                                self.shortcut(Action::Redo);
+                            }
+                            let undo = Button::new("↶");
+                            if ui
+                                .add_enabled(self.undo.can_undo(), undo)
+                                .on_hover_text(t("shortcut.undo"))
+                                .clicked()
+                            {
+                                self.shortcut(Action::Undo);
+                            }
+                            if ui.button(t("button.setting")).clicked() {
                                 self.layout = self.layout.toggle_default(Layout::Setting);
                             }
+                            if ui.button(t("button.log")).clicked() {
+                                self.layout = self.layout.toggle_default(Layout::Log);
+                            }
+                            if ui.button(t("button.help")).clicked() {
+                                self.layout = self.layout.toggle_default(Layout::Help);
+                                self.help_focus = None;
+                            }
+                            if ui.button(t("button.schedule")).clicked() {
+                                self.layout = self.layout.toggle_default(Layout::Schedule);
+                                self.schedule = None;
+                            }
+                            if ui.button(t("button.transfer")).clicked() {
+                                self.layout = self.layout.toggle_default(Layout::Transfer);
+                            }
                             ui.add_enabled_ui(self.layout == Layout::default(), |ui| {
                                 ui.add_visible_ui(self.setting.multi_account, |ui| {
                                     ui.with_layout(
@@ -570,13 +2586,23 @@ impl eframe::App for MyApp {
                                                 .add(
                                                     DragValue::new(&mut self.scroll_to_account)
                                                         .clamp_range(0..=self.account.len() - 1)
-                                                        .prefix("跳转至"),
+                                                        .prefix(t("bottom.jump")),
                                                 )
                                                 .changed();
-                                            ui.label("启用");
-                                            ui.add(TextEdit::singleline(
+                                            ui.label(t("bottom.choice"));
+                                            let response = ui.add(TextEdit::singleline(
                                                 &mut self.setting.multi_account_choice,
                                             ));
+                                            let diagnostics = validate::setting(
+                                                &self.setting,
+                                                self.account.len(),
+                                            );
+                                            Self::mark(
+                                                ui,
+                                                response,
+                                                &diagnostics,
+                                                "multi_account_choice",
+                                            );
                                         },
                                     )
                                 });
@@ -592,13 +2618,23 @@ impl eframe::App for MyApp {
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            let width = match self.layout {
+                Layout::Log => ui.available_width(),
+                Layout::Account if self.setting.multi_account && self.account_grid => {
+                    ui.available_width()
+                }
+                _ => WIDTH,
+            };
             ui.vertical_centered(|ui| {
                 ui.add_sized(
-                    egui::vec2(WIDTH, ui.available_height()),
+                    egui::vec2(width, ui.available_height()),
                     |ui: &mut egui::Ui| {
                         ui.vertical(|ui| match self.layout {
                             Layout::Setting => Self::setting(ui, self),
-                            Layout::Help => Self::setting(ui, self),
+                            Layout::Help => Self::help(ui, self),
+                            Layout::Log => Self::log(ui, self),
+                            Layout::Schedule => Self::schedule(ui, self),
+                            Layout::Transfer => Self::transfer(ui, self),
                             Layout::Account => {
                                 if self.setting.multi_account {
                                     Self::multi_account(ui, self, scroll_to_account_changed)
@@ -612,5 +2648,21 @@ impl eframe::App for MyApp {
                 )
             })
         });
+        Self::bulk(ctx, self);
+        Self::json_editor(ctx, self);
+        Self::share_import(ctx, self);
+        Self::qr(ctx, self);
+        Self::unlock(ctx, self);
+        Self::jump(ctx, self);
+        Self::shortcuts(ctx, self);
+        Self::problems(ctx, self);
+
+        // comparing the whole config is the costliest part of a frame, and
+        // without input only the places setting `config_changed` edit it
+        let input = !ctx.input().events.is_empty() || ctx.input().pointer.any_down();
+        if std::mem::take(&mut self.config_changed) || input {
+            self.undo
+                .track(&self.account, &self.setting, ctx.input().time);
+        }
     }
 }
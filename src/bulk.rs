@@ -0,0 +1,216 @@
+use std::collections::BTreeSet;
+
+use serde_json::{Map, Value};
+
+use crate::choice::Choice;
+use crate::config::object;
+use crate::data::{Account, AccountBuilder};
+use crate::help;
+
+/// Accounts selected for bulk operations.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Selection {
+    set: BTreeSet<usize>,
+    /// Last clicked account, where a shift-click range starts.
+    anchor: Option<usize>,
+}
+
+impl Selection {
+    pub fn contains(&self, idx: usize) -> bool {
+        self.set.contains(&idx)
+    }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn indices(&self) -> Vec<usize> {
+        self.set.iter().copied().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.set.clear();
+        self.anchor = None;
+    }
+
+    pub fn toggle(&mut self, idx: usize) {
+        if !self.set.remove(&idx) {
+            self.set.insert(idx);
+        }
+        self.anchor = Some(idx);
+    }
+
+    /// Selects every account between the anchor and `idx`.
+    pub fn extend_to(&mut self, idx: usize) {
+        let anchor = self.anchor.unwrap_or(idx);
+        self.set.extend(anchor.min(idx)..=anchor.max(idx));
+        self.anchor = Some(idx);
+    }
+
+    /// Handles a click on the selection toggle of account `idx`.
+    pub fn click(&mut self, idx: usize, shift: bool) {
+        if shift {
+            self.extend_to(idx);
+        } else {
+            self.toggle(idx);
+        }
+    }
+
+    /// Replaces the selection with the accounts of `choice`.
+    pub fn set_choice(&mut self, choice: &Choice, total: usize) {
+        self.set = choice.indices(total).into_iter().collect();
+        self.anchor = None;
+    }
+
+    /// Keeps the same accounts selected after they moved as `map` says.
+    pub fn remap(&mut self, map: impl Fn(usize) -> usize) {
+        self.set = self.set.iter().map(|&x| map(x)).collect();
+        self.anchor = self.anchor.map(map);
+    }
+}
+
+/// Values to set on every selected account, for the checked fields only.
+pub struct BulkEdit {
+    pub values: Map<String, Value>,
+    pub fields: BTreeSet<&'static str>,
+}
+
+impl Default for BulkEdit {
+    fn default() -> Self {
+        Self {
+            values: object(&AccountBuilder::default().build().unwrap()),
+            fields: BTreeSet::new(),
+        }
+    }
+}
+
+impl BulkEdit {
+    /// Fields offered for bulk edit: every scalar account field but the
+    /// credentials, in help order.
+    pub fn editable(&self) -> Vec<&'static str> {
+        help::ACCOUNT
+            .iter()
+            .map(|x| x.key)
+            .filter(|x| !matches!(*x, "username" | "password"))
+            .filter(|x| matches!(self.values.get(*x), Some(x) if !x.is_array()))
+            .collect()
+    }
+
+    /// The account after the edit, `None` if it changes nothing.
+    fn edited(&self, account: &Account) -> Result<Option<Account>, String> {
+        let mut x = object(account);
+        let mut changed = false;
+        for key in &self.fields {
+            if let Some(value) = self.values.get(*key) {
+                changed |= x.get(*key) != Some(value);
+                x.insert(key.to_string(), value.clone());
+            }
+        }
+        if !changed {
+            return Ok(None);
+        }
+        serde_json::from_value(Value::Object(x))
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    /// How many of the accounts at `indices` the edit would change, or why
+    /// it cannot be applied.
+    pub fn changes(&self, account: &[Account], indices: &[usize]) -> Result<usize, String> {
+        let mut count = 0;
+        for &i in indices {
+            if self.edited(&account[i])?.is_some() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Applies the edit and returns the number of changed accounts. Nothing
+    /// is written if any account fails.
+    pub fn apply(&self, account: &mut [Account], indices: &[usize]) -> Result<usize, String> {
+        let mut edited = vec![];
+        for &i in indices {
+            if let Some(x) = self.edited(&account[i])? {
+                edited.push((i, x));
+            }
+        }
+        let count = edited.len();
+        for (i, x) in edited {
+            account[i] = x;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::choice;
+    use serde_json::json;
+
+    #[test]
+    fn select() {
+        let mut selection = Selection::default();
+        selection.click(3, false);
+        selection.click(6, true);
+        assert_eq!(selection.indices(), vec![3, 4, 5, 6]);
+        selection.click(5, false);
+        assert_eq!(selection.indices(), vec![3, 4, 6]);
+        selection.click(1, true);
+        assert_eq!(selection.indices(), vec![1, 2, 3, 4, 5, 6]);
+        selection.set_choice(&choice::parse("0-1 8 #3").unwrap(), 5);
+        assert_eq!(selection.indices(), vec![0, 1]);
+        selection.click(4, true);
+        assert_eq!(selection.indices(), vec![0, 1, 4]);
+        // account 4 moved to slot 0, the others shifted up
+        selection.remap(|i| if i == 4 { 0 } else { i + 1 });
+        assert_eq!(selection.indices(), vec![0, 1, 2]);
+        selection.click(3, true);
+        assert_eq!(selection.indices(), vec![0, 1, 2, 3]);
+        selection.clear();
+        assert!(selection.is_empty());
+    }
+
+    #[test]
+    fn edit() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 4];
+        account[1].max_drug = 5;
+        account[2].username = "x".into();
+
+        let mut edit = BulkEdit::default();
+        assert!(!edit.editable().contains(&"password"));
+        assert!(!edit.editable().contains(&"max_drug_day"));
+        assert!(edit.editable().contains(&"mode"));
+        assert_eq!(edit.changes(&account, &[0, 1, 2]), Ok(0));
+
+        edit.fields.insert("max_drug");
+        edit.values.insert("max_drug".into(), json!(5));
+        edit.fields.insert("server");
+        edit.values.insert("server".into(), json!("Official"));
+        assert_eq!(edit.changes(&account, &[0, 1, 2]), Ok(2));
+
+        edit.values.insert("server".into(), json!("Bilibili"));
+        edit.values.insert("fight".into(), json!("1-7"));
+        assert_eq!(edit.changes(&account, &[0, 1, 2]), Ok(3));
+        assert_eq!(edit.apply(&mut account, &[0, 1, 2]), Ok(3));
+        assert_eq!(account[2].max_drug, 5);
+        assert_eq!(account[2].username, "x");
+        assert_eq!(account[2].server, crate::data::Server::Bilibili);
+        // unchecked fields are left alone
+        assert_eq!(account[0].fight, account[3].fight);
+        assert_eq!(account[3].max_drug, 0);
+        assert_eq!(edit.changes(&account, &[0, 1, 2]), Ok(0));
+
+        // a value the account cannot hold is reported, nothing is written
+        edit.values.insert("mode".into(), json!("Nowhere"));
+        edit.fields.insert("mode");
+        assert!(edit.changes(&account, &[0, 1, 2]).is_err());
+        assert!(edit.apply(&mut account, &[0, 1, 2]).is_err());
+        assert_eq!(account[2].mode, account[3].mode);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::data::{Account, AccountMode};
+
+/// Heights of account cards in the multi-account table, measured per mode
+/// since cards of the same mode have the same layout.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct CardHeights {
+    measured: [Option<f32>; 3],
+}
+
+fn slot(mode: &AccountMode) -> usize {
+    match mode {
+        AccountMode::Daily => 0,
+        AccountMode::ZL => 1,
+        AccountMode::Recruit => 2,
+    }
+}
+
+impl CardHeights {
+    /// Guesses used until a card of the mode has been drawn.
+    const ESTIMATE: [f32; 3] = [320.0, 120.0, 100.0];
+
+    pub fn get(&self, mode: &AccountMode) -> f32 {
+        let i = slot(mode);
+        self.measured[i].unwrap_or(Self::ESTIMATE[i])
+    }
+
+    /// Records the height a card of `mode` took, returning whether it differs
+    /// from the height the table was laid out with.
+    pub fn measure(&mut self, mode: &AccountMode, height: f32) -> bool {
+        let changed = (self.get(mode) - height).abs() > 0.5;
+        if changed || self.measured[slot(mode)].is_none() {
+            self.measured[slot(mode)] = Some(height);
+        }
+        changed
+    }
+
+    /// Heights of the rows showing the accounts at `rows`.
+    pub fn rows<'a>(
+        &'a self,
+        account: &'a [Account],
+        rows: &'a [usize],
+    ) -> impl Iterator<Item = f32> + 'a {
+        rows.iter().map(|&i| self.get(&account[i].mode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountBuilder;
+
+    #[test]
+    fn heights() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 3];
+        account[1].mode = AccountMode::ZL;
+        let mut heights = CardHeights::default();
+        let estimate: Vec<_> = heights.rows(&account, &[0, 1, 2]).collect();
+        assert_eq!(estimate, vec![320.0, 120.0, 320.0]);
+
+        assert!(heights.measure(&AccountMode::ZL, 90.0));
+        assert!(!heights.measure(&AccountMode::ZL, 90.2));
+        assert!(!heights.measure(&AccountMode::Daily, 320.0));
+        assert_eq!(heights.get(&AccountMode::Daily), 320.0);
+        assert_eq!(
+            heights.rows(&account, &[1, 0]).collect::<Vec<_>>(),
+            vec![90.0, 320.0]
+        );
+        assert_eq!(heights.get(&AccountMode::Recruit), 100.0);
+    }
+}
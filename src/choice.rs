@@ -0,0 +1,180 @@
+use std::ops::RangeInclusive;
+
+use crate::i18n::tf;
+
+/// A parsed account selection such as `"0-9 20 30-35 #5"`.
+///
+/// Ranges and single indices are separated by spaces or commas, a trailing
+/// `#n` marks the account the next run starts from.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Choice {
+    pub ranges: Vec<RangeInclusive<usize>>,
+    pub start: Option<usize>,
+}
+
+impl Choice {
+    pub fn contains(&self, idx: usize) -> bool {
+        self.ranges.iter().any(|x| x.contains(&idx))
+    }
+
+    /// Selected indices below `total`, ascending and without duplicates.
+    pub fn indices(&self, total: usize) -> Vec<usize> {
+        (0..total).filter(|&i| self.contains(i)).collect()
+    }
+}
+
+pub fn parse(text: &str) -> Result<Choice, String> {
+    let mut choice = Choice::default();
+    let (text, start) = match text.split_once('#') {
+        Some((text, start)) => (text, Some(start)),
+        None => (text, None),
+    };
+    if let Some(start) = start {
+        let start = start.trim();
+        choice.start = Some(
+            start
+                .parse()
+                .map_err(|_| tf("choice.bad_start", &[&start]))?,
+        );
+    }
+    for token in text.split(|c: char| c.is_whitespace() || c == ',' || c == '，') {
+        if token.is_empty() {
+            continue;
+        }
+        let number = |x: &str| {
+            x.trim()
+                .parse::<usize>()
+                .map_err(|_| tf("choice.bad_range", &[&token]))
+        };
+        let range = match token.split_once('-') {
+            Some((a, b)) => number(a)?..=number(b)?,
+            None => number(token)?..=number(token)?,
+        };
+        if range.is_empty() {
+            return Err(tf("choice.bad_range", &[&token]));
+        }
+        choice.ranges.push(range);
+    }
+    Ok(choice)
+}
+
+/// The selection `text` after the accounts moved as `map` says, where `map`
+/// only changes indices in `window`. Text that does not parse, or selects
+/// nothing in the window, is returned unchanged.
+pub fn remap(text: &str, window: RangeInclusive<usize>, map: impl Fn(usize) -> usize) -> String {
+    let Ok(choice) = parse(text) else {
+        return text.to_string();
+    };
+    let (low, high) = (*window.start(), *window.end());
+    let mut ranges = vec![];
+    let (mut before, mut moved) = (vec![], vec![]);
+    for range in &choice.ranges {
+        let (a, b) = (*range.start(), *range.end());
+        if a < low {
+            ranges.push(a..=b.min(low - 1));
+        }
+        if b > high {
+            ranges.push(a.max(high + 1)..=b);
+        }
+        before.extend(a.max(low)..=b.min(high));
+    }
+    moved.extend(before.iter().map(|&x| map(x)));
+    moved.sort_unstable();
+    before.sort_unstable();
+    let start = choice.start.map(&map);
+    if moved == before && start == choice.start {
+        return text.to_string();
+    }
+    ranges.extend(moved.into_iter().map(|x| x..=x));
+    ranges.sort_by_key(|x| *x.start());
+
+    let mut merged: Vec<RangeInclusive<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if *range.start() <= last.end() + 1 => {
+                *last = *last.start()..=*range.end().max(last.end());
+            }
+            _ => merged.push(range),
+        }
+    }
+    let mut parts: Vec<_> = merged
+        .iter()
+        .map(|x| match x.start() == x.end() {
+            true => x.start().to_string(),
+            false => format!("{}-{}", x.start(), x.end()),
+        })
+        .collect();
+    if let Some(start) = start {
+        parts.push(format!("#{start}"));
+    }
+    parts.join(" ")
+}
+
+/// Formats ascending indices back into the shortest selection, e.g. `"0-3 7"`.
+pub fn format(indices: &[usize]) -> String {
+    let mut parts = Vec::new();
+    let mut iter = indices.iter().copied().peekable();
+    while let Some(first) = iter.next() {
+        let mut last = first;
+        while iter.peek() == Some(&(last + 1)) {
+            last += 1;
+            iter.next();
+        }
+        parts.push(if first == last {
+            first.to_string()
+        } else {
+            format!("{first}-{last}")
+        });
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ranges() {
+        let choice = parse("0-9 20, 30-35 #5").unwrap();
+        assert_eq!(choice.ranges, vec![0..=9, 20..=20, 30..=35]);
+        assert_eq!(choice.start, Some(5));
+        assert!(choice.contains(20));
+        assert!(!choice.contains(21));
+        assert_eq!(choice.indices(32).len(), 10 + 1 + 2);
+
+        assert_eq!(parse("").unwrap(), Choice::default());
+        assert_eq!(parse(" 3 ").unwrap().ranges, vec![3..=3]);
+        assert!(parse("a-3").is_err());
+        assert!(parse("5-3").is_err());
+        assert!(parse("1 #x").is_err());
+    }
+
+    #[test]
+    fn format_indices() {
+        assert_eq!(format(&[]), "");
+        assert_eq!(format(&[0, 1, 2, 3, 7, 9, 10]), "0-3 7 9-10");
+        let choice = parse(&format(&[1, 2, 5])).unwrap();
+        assert_eq!(choice.indices(10), vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn remap_moved() {
+        let swap = |i| match i {
+            2 => 7,
+            7 => 2,
+            i => i,
+        };
+        assert_eq!(remap("0-2 #7", 2..=7, swap), "0-1 7 #2");
+        assert_eq!(remap("0-9999", 2..=7, swap), "0-9999");
+        assert_eq!(remap("3-4, 20", 2..=7, swap), "3-4, 20");
+        assert_eq!(remap("x", 2..=7, swap), "x");
+        // moving 1 to 3 shifts 2 and 3 down
+        let shift = |i| match i {
+            1 => 3,
+            2 | 3 => i - 1,
+            i => i,
+        };
+        assert_eq!(remap("1 5", 1..=3, shift), "3 5");
+        assert_eq!(remap("0-3", 1..=3, shift), "0-3");
+    }
+}
@@ -0,0 +1,462 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::{self, LoginResult};
+use crate::choice;
+use crate::config::{self, Config};
+use crate::csv;
+use crate::data::{self, default_account, is_default, Account, Setting};
+use crate::file;
+use crate::i18n::{self, t, tf};
+use crate::json::{self, Scope};
+use crate::redact;
+use crate::validate::{self, Severity};
+use crate::vault::{self, Vault};
+
+/// Environment variable holding the passphrase of an encrypted config.
+const PASSPHRASE: &str = "MIZUKI_PASSPHRASE";
+const COMMANDS: &[&str] = &["validate", "list", "set", "check", "convert", "help"];
+/// Options followed by a value, `-o` being short for `--output`.
+const OPTIONS: &[&str] = &["--format", "--from", "--to", "--output"];
+/// Options without a value.
+const FLAGS: &[&str] = &["--show-secrets"];
+/// Account fields `list` shows next to the index, the username masked
+/// unless `--show-secrets` is given.
+const LIST: &[&str] = &["username", "server", "mode", "fight"];
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Format {
+    /// The document the app opens and saves.
+    Json,
+    Csv,
+    Script,
+}
+
+impl Format {
+    fn named(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "script" | "txt" | "conf" | "cfg" => Ok(Self::Script),
+            _ => Err(tf("cli.unknown_format", &[&name])),
+        }
+    }
+
+    /// The format given by `option`, or else by the extension of `path`.
+    fn of(path: &str, option: Option<&String>) -> Result<Self, String> {
+        match option {
+            Some(x) => Self::named(x),
+            None => Self::named(path.rsplit_once('.').map_or("", |x| x.1)),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default)]
+struct Args {
+    positional: Vec<String>,
+    options: BTreeMap<&'static str, String>,
+    flags: BTreeSet<&'static str>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut out = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "-" || !arg.starts_with('-') {
+                out.positional.push(arg.clone());
+                continue;
+            }
+            if let Some(flag) = FLAGS.iter().find(|x| *x == arg) {
+                out.flags.insert(flag);
+                continue;
+            }
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let name = if name == "-o" { "--output" } else { name };
+            let name = OPTIONS
+                .iter()
+                .find(|x| **x == name)
+                .ok_or_else(|| tf("cli.unknown_option", &[&arg]))?;
+            let value = value
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| tf("cli.no_option_value", &[name]))?;
+            out.options.insert(name, value);
+        }
+        Ok(out)
+    }
+
+    fn format(&self, path: &str, option: &str) -> Result<Format, String> {
+        Format::of(path, self.options.get(option))
+    }
+}
+
+/// A config read from a file, decrypted when it was encrypted and the
+/// passphrase is set, so saving can encrypt it again.
+struct Loaded {
+    account: Vec<Account>,
+    setting: Setting,
+    vault: Option<Vault>,
+}
+
+fn read(text: &str, format: Format) -> Result<(Vec<Account>, Setting), String> {
+    let mut account: Vec<_> = (0..data::TOTAL).map(default_account).collect();
+    let mut setting = Setting::default();
+    match format {
+        Format::Json => {
+            let document = json::parse(Scope::All, text, data::TOTAL)?;
+            json::apply(Scope::All, &document, &mut account, &mut setting);
+        }
+        Format::Csv => {
+            let import = csv::import(text, &account)?;
+            if !import.errors.is_empty() {
+                return Err(import.errors.join("\n"));
+            }
+            import.apply(&mut account);
+        }
+        Format::Script => {
+            let config = config::decode(text, data::TOTAL)?;
+            (account, setting) = (config.account, config.setting);
+        }
+    }
+    Ok((account, setting))
+}
+
+fn write(account: &[Account], setting: &Setting, format: Format) -> String {
+    match format {
+        Format::Json => {
+            let document = json::document(Scope::All, account, setting);
+            serde_json::to_string_pretty(&document).unwrap()
+        }
+        Format::Csv => csv::export(account),
+        Format::Script => config::encode(&Config {
+            account: account.to_vec(),
+            setting: setting.clone(),
+        }),
+    }
+}
+
+/// Reads the config at `path`, or standard input for `-`.
+fn load(path: &str, format: Format) -> Result<Loaded, String> {
+    let text = if path == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| e.to_string())?;
+        text
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?
+    };
+    let (mut account, mut setting) = read(&text, format)?;
+    i18n::set(setting.language);
+
+    let mut vault = None;
+    let samples = vault::locked(&account, &setting);
+    let passphrase = std::env::var(PASSPHRASE)
+        .ok()
+        .filter(|_| !samples.is_empty());
+    if let Some(passphrase) = passphrase {
+        let x = Vault::unlock(&passphrase, &samples)?;
+        vault::decrypt_all(&x, &mut account, &mut setting)?;
+        vault = Some(x);
+    }
+    Ok(Loaded {
+        account,
+        setting,
+        vault,
+    })
+}
+
+/// Writes `loaded` to `path`, or standard output for `-`, encrypting its
+/// secrets again if it was encrypted.
+fn save(
+    loaded: &Loaded,
+    path: &str,
+    format: Format,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<(), String> {
+    let mut account = loaded.account.clone();
+    let mut setting = loaded.setting.clone();
+    if let Some(vault) = &loaded.vault {
+        vault::encrypt_all(vault, &mut account, &mut setting);
+    }
+    if format == Format::Csv {
+        let _ = writeln!(err, "{}", t("cli.csv_accounts"));
+    }
+    let text = write(&account, &setting, format);
+    if path == "-" {
+        let _ = writeln!(out, "{text}");
+    } else {
+        let saved = file::save_as(path, &text)?;
+        let _ = writeln!(err, "{}", tf("toast.saved", &[&saved]));
+    }
+    Ok(())
+}
+
+/// `x` with `key` set to `value`, written like in the script format.
+fn assign<T: Serialize + DeserializeOwned>(x: &T, key: &str, value: &str) -> Result<T, String> {
+    let mut map = config::object(x);
+    let bad = || tf("config.bad_value", &[&key, &value]);
+    let like = map.get(key).ok_or_else(|| tf("config.unknown", &[&key]))?;
+    let value = config::parse(like, value).ok_or_else(bad)?;
+    map.insert(key.to_string(), value);
+    serde_json::from_value(Value::Object(map)).map_err(|_| bad())
+}
+
+/// Runs `command` with `args`, returning the exit code. Results go to `out`,
+/// progress to `err`.
+fn execute(
+    command: &str,
+    args: &[String],
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args)?;
+    let usage = || t("cli.usage").to_string();
+    match (command, args.positional.as_slice()) {
+        ("validate", [path]) => {
+            let loaded = load(path, args.format(path, "--format")?)?;
+            let list = validate::all(&loaded.account, &loaded.setting);
+            for x in &list {
+                let _ = writeln!(out, "{}", x.to_text());
+            }
+            let errors = list
+                .iter()
+                .filter(|x| x.severity == Severity::Error)
+                .count();
+            let warnings = list.len() - errors;
+            let _ = writeln!(err, "{}", tf("cli.summary", &[&errors, &warnings]));
+            Ok(i32::from(errors > 0))
+        }
+        ("list", [path, selection @ ..]) if selection.len() <= 1 => {
+            let loaded = load(path, args.format(path, "--format")?)?;
+            let indices = match selection.first() {
+                Some(x) => choice::parse(x)?.indices(loaded.account.len()),
+                None => (0..loaded.account.len())
+                    .filter(|&i| !is_default(&loaded.account[i], i))
+                    .collect(),
+            };
+            let show = args.flags.contains("--show-secrets");
+            let _ = writeln!(out, "index\t{}", LIST.join("\t"));
+            for idx in indices {
+                let map = serde_json::to_value(&loaded.account[idx]).unwrap();
+                let cells: Vec<_> = LIST
+                    .iter()
+                    .map(|x| match config::format(&map[x]) {
+                        text if *x == "username" && !show && !text.is_empty() => {
+                            redact::mask(&text)
+                        }
+                        text => text,
+                    })
+                    .collect();
+                let _ = writeln!(out, "{idx}\t{}", cells.join("\t"));
+            }
+            Ok(0)
+        }
+        ("set", [path, selection, assignments @ ..]) if !assignments.is_empty() => {
+            let format = args.format(path, "--format")?;
+            let mut loaded = load(path, format)?;
+            let indices = choice::parse(selection)?.indices(loaded.account.len());
+            for assignment in assignments {
+                let (key, value) = assignment
+                    .split_once('=')
+                    .ok_or_else(|| tf("config.no_value", &[&assignment]))?;
+                match key.strip_prefix("setting.") {
+                    Some(key) => loaded.setting = assign(&loaded.setting, key, value)?,
+                    None => {
+                        for &idx in &indices {
+                            loaded.account[idx] = assign(&loaded.account[idx], key, value)?;
+                        }
+                    }
+                }
+            }
+            let _ = writeln!(err, "{}", tf("cli.set", &[&indices.len()]));
+            let output = args.options.get("--output").unwrap_or(path);
+            save(&loaded, output, args.format(output, "--to")?, out, err)?;
+            Ok(0)
+        }
+        ("check", [path, selection @ ..]) if selection.len() <= 1 => {
+            let loaded = load(path, args.format(path, "--format")?)?;
+            if !vault::locked(&loaded.account, &loaded.setting).is_empty() {
+                return Err(tf("cli.locked", &[&PASSPHRASE]));
+            }
+            let selection = match selection.first() {
+                Some(x) => x.clone(),
+                None if loaded.setting.multi_account => loaded.setting.multi_account_choice.clone(),
+                None => "0".into(),
+            };
+            let mut failed = 0;
+            for idx in choice::parse(&selection)?.indices(loaded.account.len()) {
+                let x = &loaded.account[idx];
+                if x.username.is_empty() {
+                    continue;
+                }
+                let result = api::login(&x.username, &x.password, &x.server);
+                if result != LoginResult::Success {
+                    failed += 1;
+                }
+                let username = redact::mask(&x.username);
+                let _ = writeln!(out, "{idx}\t{username}\t{}", result.str());
+            }
+            Ok(i32::from(failed > 0))
+        }
+        ("convert", [input, output]) => {
+            let loaded = load(input, args.format(input, "--from")?)?;
+            save(&loaded, output, args.format(output, "--to")?, out, err)?;
+            Ok(0)
+        }
+        ("help", []) => {
+            let _ = writeln!(out, "{}", usage());
+            Ok(0)
+        }
+        _ => Err(usage()),
+    }
+}
+
+/// Runs the command-line tool when `args`, without the program name, start
+/// with one of its commands, and returns the exit code. Returns `None` when
+/// the window should open instead.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+    if !COMMANDS.contains(&command.as_str()) {
+        return None;
+    }
+    let (mut out, mut err) = (std::io::stdout(), std::io::stderr());
+    match execute(command, &args[1..], &mut out, &mut err) {
+        Ok(code) => Some(code),
+        Err(e) => {
+            let _ = writeln!(err, "{e}");
+            Some(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Server;
+
+    /// A file in the temporary directory, removed when dropped.
+    struct Temp(String);
+
+    impl Temp {
+        fn new(name: &str, text: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("mizuki_cli_{}_{name}", std::process::id()));
+            let path = path.display().to_string();
+            std::fs::write(&path, text).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for Temp {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn exec(args: &[&str]) -> (Result<i32, String>, String) {
+        let args: Vec<_> = args.iter().map(|x| x.to_string()).collect();
+        let (mut out, mut err) = (vec![], vec![]);
+        let result = execute(&args[0], &args[1..], &mut out, &mut err);
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    fn sample() -> String {
+        let mut account: Vec<_> = (0..data::TOTAL).map(default_account).collect();
+        account[1].username = "13800000000".into();
+        account[1].password = "pw".into();
+        account[3].fight = "ce-6".into();
+        write(&account, &Setting::default(), Format::Json)
+    }
+
+    #[test]
+    fn arguments() {
+        assert_eq!(run(&["--config".into(), "x.json".into()]), None);
+        assert_eq!(run(&[]), None);
+        let args: Vec<String> = ["a", "-o", "b", "--to=csv", "-"]
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+        let parsed = Args::parse(&args).unwrap();
+        assert_eq!(parsed.positional, vec!["a", "-"]);
+        assert_eq!(parsed.options["--output"], "b");
+        assert_eq!(parsed.options["--to"], "csv");
+        assert!(Args::parse(&["--colour".into()]).is_err());
+        assert!(Args::parse(&["--show-secrets".into()])
+            .unwrap()
+            .flags
+            .contains("--show-secrets"));
+        assert!(Args::parse(&["--from".into()]).is_err());
+
+        assert_eq!(Format::of("a.CSV", None), Ok(Format::Csv));
+        assert_eq!(Format::of("-", Some(&"script".into())), Ok(Format::Script));
+        assert!(Format::of("a.xml", None).is_err());
+        assert!(exec(&["set", "a.json", "0"]).0.is_err());
+        assert_eq!(exec(&["help"]).0, Ok(0));
+    }
+
+    #[test]
+    fn convert_round_trip() {
+        let json = Temp::new("round.json", &sample());
+        let script = Temp::new("round.txt", "");
+        let csv = Temp::new("round.csv", "");
+        assert_eq!(exec(&["convert", &json.0, &script.0]).0, Ok(0));
+        assert!(std::fs::read_to_string(&script.0)
+            .unwrap()
+            .contains("account3.fight=ce-6"));
+        assert_eq!(exec(&["convert", &script.0, &csv.0]).0, Ok(0));
+        let (result, out) = exec(&["convert", &csv.0, "-", "--to", "json"]);
+        assert_eq!(result, Ok(0));
+        let (account, _) = read(&out, Format::Json).unwrap();
+        let (expected, _) = read(&sample(), Format::Json).unwrap();
+        assert_eq!(account, expected);
+    }
+
+    #[test]
+    fn set_list_and_validate() {
+        let json = Temp::new("set.json", &sample());
+        let (result, out) = exec(&[
+            "set",
+            &json.0,
+            "2-3",
+            "server=Bilibili",
+            "fight=1-7",
+            "setting.multi_account=true",
+            "setting.multi_account_choice=0-3",
+        ]);
+        assert_eq!(result, Ok(0));
+        assert_eq!(out, "");
+        let text = std::fs::read_to_string(&json.0).unwrap();
+        let (account, setting) = read(&text, Format::Json).unwrap();
+        assert_eq!(account[2].server, Server::Bilibili);
+        assert_eq!(account[3].fight, "1-7");
+        assert_eq!(account[1].fight, default_account(1).fight);
+        assert!(setting.multi_account);
+
+        let (result, out) = exec(&["list", &json.0]);
+        assert_eq!(result, Ok(0));
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines[0], "index\tusername\tserver\tmode\tfight");
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("1\t138****0000\tOfficial"));
+        let (_, out) = exec(&["list", &json.0, "1", "--show-secrets"]);
+        assert!(out.contains("1\t13800000000\tOfficial"));
+        assert!(exec(&["list", &json.0, "2"]).1.contains("2\t\tBilibili"));
+
+        assert_eq!(exec(&["validate", &json.0]).0, Ok(0));
+        assert!(exec(&["set", &json.0, "0", "colour=red"]).0.is_err());
+        assert!(exec(&["set", &json.0, "0", "max_drug=lots"]).0.is_err());
+        exec(&["set", &json.0, "0", "fight=!!"]).0.unwrap();
+        let (result, out) = exec(&["validate", &json.0]);
+        assert_eq!(result, Ok(1));
+        assert!(out.contains("#0 fight"), "{out}");
+    }
+}
@@ -0,0 +1,232 @@
+use serde_json::{Map, Value};
+
+use crate::data::{default_account, is_default, Account, Setting};
+use crate::i18n::tf;
+use crate::share::LOCAL;
+
+/// Everything the script runs with.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Config {
+    pub account: Vec<Account>,
+    pub setting: Setting,
+}
+
+impl Config {
+    /// The default configuration with `total` account slots.
+    pub fn new(total: usize) -> Self {
+        Self {
+            account: (0..total).map(default_account).collect(),
+            setting: Setting::default(),
+        }
+    }
+}
+
+/// The fields of an account or setting by name.
+pub(crate) fn object<T: serde::Serialize>(x: &T) -> Map<String, Value> {
+    match serde_json::to_value(x) {
+        Ok(Value::Object(x)) => x,
+        _ => unreachable!(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some(x) => out.push(x),
+                None => out.push('\\'),
+            },
+            (c, false) => out.push(c),
+        }
+    }
+    out
+}
+
+/// One value as written after `=`.
+pub fn format(value: &Value) -> String {
+    match value {
+        Value::String(x) => escape(x),
+        Value::Array(x) => x.iter().map(format).collect::<Vec<_>>().join(" "),
+        x => x.to_string(),
+    }
+}
+
+/// Parses `text` into a value of the same type as `like`.
+pub fn parse(like: &Value, text: &str) -> Option<Value> {
+    match like {
+        Value::Bool(_) => text.parse().ok().map(Value::Bool),
+        Value::Number(_) => text.parse::<u64>().ok().map(Value::from),
+        Value::String(_) => Some(Value::String(unescape(text))),
+        Value::Array(_) => text
+            .split_whitespace()
+            .map(|x| x.parse::<u64>().ok().map(Value::from))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        _ => None,
+    }
+}
+
+/// Writes the key/value format the Mizuki script reads: one `key=value` per
+/// line, setting fields under their own name and account fields as
+/// `account<idx>.<field>`. Accounts left at their defaults are omitted, as
+/// are the machine-local settings the script does not know.
+/// Strings escape `\` and line breaks, lists are space separated.
+pub fn encode(config: &Config) -> String {
+    let mut lines = vec!["# Mizuki".to_string()];
+    for (key, value) in object(&config.setting) {
+        if LOCAL.contains(&key.as_str()) {
+            continue;
+        }
+        lines.push(format!("{key}={}", format(&value)));
+    }
+    for (idx, account) in config.account.iter().enumerate() {
+        if is_default(account, idx) {
+            continue;
+        }
+        for (key, value) in object(account) {
+            lines.push(format!("account{idx}.{key}={}", format(&value)));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Reads [`encode`]'s format into a default configuration of `total`
+/// accounts. Fields not mentioned keep their defaults.
+pub fn decode(text: &str, total: usize) -> Result<Config, String> {
+    let mut setting = object(&Setting::default());
+    let mut account: Vec<Option<Map<String, Value>>> = vec![None; total];
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |e: String| tf("report.bad_line", &[&(i + 1), &e]);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error(tf("config.no_value", &[&line])))?;
+        let key = key.trim();
+        let (idx, field) = match key.strip_prefix("account").and_then(|x| x.split_once('.')) {
+            Some((idx, field)) => {
+                let idx: usize = idx
+                    .parse()
+                    .ok()
+                    .filter(|&x| x < total)
+                    .ok_or_else(|| error(tf("validate.bad_index", &[&idx])))?;
+                (Some(idx), field)
+            }
+            None => (None, key),
+        };
+        let map = match idx {
+            Some(idx) => account[idx].get_or_insert_with(|| object(&default_account(idx))),
+            None => &mut setting,
+        };
+        let bad_value = || error(tf("config.bad_value", &[&field, &value]));
+        let like = map
+            .get(field)
+            .ok_or_else(|| error(tf("config.unknown", &[&field])))?;
+        let parsed = parse(like, value.trim_end_matches('\r')).ok_or_else(bad_value)?;
+        let old = map.insert(field.to_string(), parsed);
+        // enums are strings here, so only the struct knows which are valid
+        let valid = match idx {
+            Some(_) => serde_json::from_value::<Account>(Value::Object(map.clone())).is_ok(),
+            None => serde_json::from_value::<Setting>(Value::Object(map.clone())).is_ok(),
+        };
+        if !valid {
+            map.insert(field.to_string(), old.unwrap());
+            return Err(bad_value());
+        }
+    }
+
+    let mut config = Config::new(total);
+    config.setting = serde_json::from_value(Value::Object(setting)).unwrap();
+    for (x, map) in config.account.iter_mut().zip(account) {
+        if let Some(map) = map {
+            *x = serde_json::from_value(Value::Object(map)).unwrap();
+        }
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{AccountMode, Server};
+
+    const GOLDEN: &str = include_str!("../tests/fixtures/script_config.txt");
+
+    fn sample() -> Config {
+        let mut config = Config::new(3);
+        config.setting.multi_account = true;
+        config.setting.multi_account_choice = "0-2 #1".into();
+        config.setting.qq_notify = "12345".into();
+        let x = &mut config.account[0];
+        // the default is the current date, which a fixture cannot hold
+        x.allow_after = String::new();
+        x.username = "13800000000".into();
+        x.password = "pass=word\\1\nx".into();
+        x.fight = "ce-6 1-7".into();
+        x.max_drug_day = vec![0, 0, 0, 0, 0, 1, 2];
+        let x = &mut config.account[2];
+        x.allow_after = String::new();
+        x.username = "bili".into();
+        x.server = Server::Bilibili;
+        x.mode = AccountMode::ZL;
+        x.zl_max_level = 30;
+        config
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut config = sample();
+        config.account[2].password = "ends in a carriage return\r".into();
+        let text = encode(&config);
+        assert!(!text.contains("language=") && !text.contains("runner_url="));
+        assert_eq!(decode(&text, 3).unwrap(), config);
+        assert_eq!(decode("", 3).unwrap(), Config::new(3));
+        let setting = object(&Setting::default()).len() - LOCAL.len();
+        assert_eq!(encode(&Config::new(3)).lines().count(), 1 + setting);
+    }
+
+    #[test]
+    fn defaults_from_another_day() {
+        // a config whose default slots were made on an earlier day
+        let mut config = Config::new(3);
+        for x in &mut config.account {
+            x.allow_after = "2022-01-01 00:00".into();
+        }
+        let text = encode(&config);
+        assert!(text.contains("account0.allow_after=2022-01-01 00:00"));
+        assert!(!text.contains("account1."));
+        assert!(!text.contains("account2."));
+    }
+
+    #[test]
+    fn golden() {
+        assert_eq!(encode(&sample()), GOLDEN.replace("\r\n", "\n"));
+        assert_eq!(decode(GOLDEN, 3).unwrap(), sample());
+    }
+
+    #[test]
+    fn errors() {
+        let e = decode("crontab=4:00\nnothing=1", 3).unwrap_err();
+        assert!(e.contains('2') && e.contains("nothing"), "{e}");
+        assert!(decode("multi_account=yes", 3).is_err());
+        assert!(decode("account3.fight=1-7", 3).is_err());
+        assert!(decode("account1.max_drug_day=1 x", 3).is_err());
+        assert!(decode("crontab", 3).is_err());
+        assert!(decode("account0.server=Nowhere", 3).is_err());
+        let config = decode("account1.fight= a=b \n # c", 3).unwrap();
+        assert_eq!(config.account[1].fight, " a=b ");
+    }
+}
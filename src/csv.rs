@@ -0,0 +1,363 @@
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::config::{self, object};
+use crate::data::{is_default, Account};
+use crate::help;
+use crate::i18n::{self, t, tf, Lang};
+
+/// Columns exported first, in spreadsheet order.
+const BASE: &[&str] = &["username", "password", "server", "mode"];
+
+/// Header names teams commonly use besides the field keys and help labels.
+const ALIASES: &[(&str, &str)] = &[
+    ("序号", "index"),
+    ("编号", "index"),
+    ("#", "index"),
+    ("用户名", "username"),
+    ("手机号", "username"),
+    ("服务器", "server"),
+    ("区服", "server"),
+];
+
+/// Display names accepted for enum cells next to their serde names.
+const VARIANTS: &[(&str, &str, &str)] = &[
+    ("server", "Official", "server.official"),
+    ("server", "Bilibili", "server.bilibili"),
+    ("mode", "Daily", "mode.daily"),
+    ("mode", "ZL", "mode.zl"),
+    ("mode", "Recruit", "mode.recruit"),
+];
+
+/// The field a header cell names, `index` for the slot column.
+fn column(name: &str) -> Option<&'static str> {
+    let name = name.trim();
+    if name.eq_ignore_ascii_case("index") {
+        return Some("index");
+    }
+    ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map(|(_, key)| *key)
+        .or_else(|| help::ACCOUNT.iter().find(|x| x.named(name)).map(|x| x.key))
+}
+
+/// First characters that make spreadsheets run a cell as a formula.
+const FORMULA: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// Whether a string cell gets a `'` in front, which spreadsheets take as
+/// "text" and [`cell_value`] removes again. Cells already starting with `'`
+/// get one too so they read back unchanged.
+fn escaped(text: &str) -> bool {
+    text.starts_with(FORMULA) || text.starts_with('\'')
+}
+
+/// Cells hold strings as they are, CSV quoting takes care of separators.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(x) if escaped(x) => format!("'{x}"),
+        Value::String(x) => x.clone(),
+        x => config::format(x),
+    }
+}
+
+/// Parses a cell like `like`; an empty cell keeps non-string values.
+fn cell_value(field: &str, like: &Value, text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if let Some((_, name, _)) = VARIANTS.iter().find(|(key, name, label)| {
+        *key == field
+            && (name.eq_ignore_ascii_case(trimmed)
+                || Lang::ALL
+                    .iter()
+                    .any(|&lang| i18n::tr(lang, label) == trimmed))
+    }) {
+        return Some(Value::String(name.to_string()));
+    }
+    match (like, trimmed) {
+        (Value::String(_), _) => {
+            let text = text
+                .strip_prefix('\'')
+                .filter(|x| escaped(x))
+                .unwrap_or(text);
+            Some(Value::String(text.to_string()))
+        }
+        (_, "") => Some(like.clone()),
+        (Value::Bool(_), "1" | "yes" | "是" | "√") => Some(Value::Bool(true)),
+        (Value::Bool(_), "0" | "no" | "否") => Some(Value::Bool(false)),
+        _ => config::parse(like, trimmed),
+    }
+}
+
+fn quote(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) || cell.trim() != cell {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Splits RFC 4180 text into records, each with the line it starts on.
+fn records(text: &str) -> Vec<(usize, Vec<String>)> {
+    let mut out = vec![];
+    let mut record = vec![];
+    let mut cell = String::new();
+    let (mut line, mut start) = (1, 1);
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.is_empty() => quoted = true,
+            '\n' if quoted => {
+                line += 1;
+                cell.push(c);
+            }
+            ',' if !quoted => record.push(std::mem::take(&mut cell)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut cell));
+                out.push((start, std::mem::take(&mut record)));
+                line += 1;
+                start = line;
+            }
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !record.is_empty() {
+        record.push(cell);
+        out.push((start, record));
+    }
+    out
+}
+
+/// Accounts that differ from their defaults, one row each, with the slot
+/// index and every field. Starts with a BOM so spreadsheets read UTF-8.
+pub fn export(account: &[Account]) -> String {
+    let keys: Vec<_> = BASE
+        .iter()
+        .copied()
+        .chain(
+            help::ACCOUNT
+                .iter()
+                .map(|x| x.key)
+                .filter(|x| !BASE.contains(x)),
+        )
+        .collect();
+    let mut out = String::from("\u{feff}index,");
+    out += &keys.join(",");
+    out += "\r\n";
+    for (idx, x) in account.iter().enumerate() {
+        if is_default(x, idx) {
+            continue;
+        }
+        let map = object(x);
+        let cells: Vec<_> = keys.iter().map(|key| quote(&cell(&map[*key]))).collect();
+        out += &format!("{idx},{}\r\n", cells.join(","));
+    }
+    out
+}
+
+/// An account read from a CSV row, not yet written into its slot.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Row {
+    pub line: usize,
+    pub idx: usize,
+    pub account: Account,
+    /// Fields whose value changes.
+    pub changed: Vec<&'static str>,
+    /// Whether the slot held a configured account before.
+    pub overwrite: bool,
+}
+
+/// The result of reading a CSV: the rows that can be applied and a message
+/// for each row that cannot.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Import {
+    pub rows: Vec<Row>,
+    pub errors: Vec<String>,
+}
+
+impl Import {
+    pub fn apply(&self, account: &mut [Account]) {
+        for row in &self.rows {
+            account[row.idx] = row.account.clone();
+        }
+    }
+}
+
+/// Reads rows into the slots their `index` column names. Columns left out
+/// keep the slot's values; unknown columns are reported and ignored. Fails
+/// only when the header has no index column.
+pub fn import(text: &str, account: &[Account]) -> Result<Import, String> {
+    let mut records = records(text).into_iter();
+    let (_, header) = records
+        .next()
+        .ok_or_else(|| t("csv.no_index").to_string())?;
+    let mut out = Import::default();
+    let columns: Vec<_> = header
+        .iter()
+        .map(|name| {
+            let x = column(name);
+            if x.is_none() && !name.trim().is_empty() {
+                let e = tf("csv.unknown_column", &[&name.trim()]);
+                out.errors.push(tf("report.bad_line", &[&1, &e]));
+            }
+            x
+        })
+        .collect();
+    let index = columns
+        .iter()
+        .position(|x| x == &Some("index"))
+        .ok_or_else(|| t("csv.no_index").to_string())?;
+
+    let mut seen = BTreeSet::new();
+    for (line, record) in records {
+        if record.iter().all(|x| x.trim().is_empty()) {
+            continue;
+        }
+        let error = |e: String| tf("report.bad_line", &[&line, &e]);
+        if record.len() != header.len() {
+            let e = tf("csv.cells", &[&record.len(), &header.len()]);
+            out.errors.push(error(e));
+            continue;
+        }
+        let idx = match record[index].trim().parse::<usize>() {
+            Ok(x) if x < account.len() => x,
+            _ => {
+                let e = tf("validate.bad_index", &[&record[index].trim()]);
+                out.errors.push(error(e));
+                continue;
+            }
+        };
+        if !seen.insert(idx) {
+            out.errors.push(error(tf("csv.duplicate", &[&idx])));
+            continue;
+        }
+
+        let mut map = object(&account[idx]);
+        let mut bad = vec![];
+        for (field, cell) in columns.iter().zip(&record) {
+            let field = match field {
+                Some(x) if *x != "index" => *x,
+                _ => continue,
+            };
+            match cell_value(field, &map[field], cell) {
+                Some(value) => {
+                    map.insert(field.to_string(), value);
+                }
+                None => bad.push(tf("config.bad_value", &[&field, &cell])),
+            }
+        }
+        let parsed = serde_json::from_value::<Account>(Value::Object(map));
+        match (bad.is_empty(), parsed) {
+            (true, Ok(x)) => {
+                let before = object(&account[idx]);
+                let after = object(&x);
+                let changed = help::ACCOUNT
+                    .iter()
+                    .map(|x| x.key)
+                    .filter(|key| before[*key] != after[*key])
+                    .collect();
+                out.rows.push(Row {
+                    line,
+                    idx,
+                    changed,
+                    overwrite: !is_default(&account[idx], idx),
+                    account: x,
+                });
+            }
+            (_, parsed) => {
+                if let Err(e) = parsed {
+                    bad.push(e.to_string());
+                }
+                out.errors.push(error(bad.join(", ")));
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{default_account, AccountMode, Server};
+
+    fn accounts() -> Vec<Account> {
+        (0..4).map(default_account).collect()
+    }
+
+    #[test]
+    fn parse_records() {
+        let text = "\u{feff}a,b\r\n\"x, \"\"y\"\"\",\"1\n2\"\n\n3,";
+        assert_eq!(
+            records(text),
+            vec![
+                (1, vec!["a".into(), "b".into()]),
+                (2, vec!["x, \"y\"".into(), "1\n2".into()]),
+                (4, vec!["".into()]),
+                (5, vec!["3".into(), "".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut account = accounts();
+        account[1].username = "13800000000".into();
+        account[1].password = "a,\"b\"\\n".into();
+        account[1].fight = " 1-7".into();
+        account[2].username = "+8613800000000".into();
+        account[2].password = "=HYPERLINK(\"x\")".into();
+        account[2].fight = "'-".into();
+        account[3].server = Server::Bilibili;
+        account[3].mode = AccountMode::Recruit;
+        account[3].max_drug_day = vec![1, 2];
+        let text = export(&account);
+        assert_eq!(text.lines().count(), 4);
+        assert!(text.contains("\r\n2,'+8613800000000,\"'=HYPERLINK(\"\"x\"\")\","));
+        assert!(text.contains(",''-,"));
+
+        let import = import(&text, &accounts()).unwrap();
+        assert!(import.errors.is_empty(), "{:?}", import.errors);
+        assert_eq!(import.rows.len(), 3);
+        assert!(import.rows.iter().all(|x| !x.overwrite));
+        let mut copy = accounts();
+        import.apply(&mut copy);
+        assert_eq!(copy, account);
+    }
+
+    #[test]
+    fn header_mapping_and_errors() {
+        let mut account = accounts();
+        account[2].username = "old".into();
+        account[2].fight = "ce-6".into();
+        let text = "序号,用户名,密码,服务,Mode,color\n\
+                    2,138,pw,B服,Roguelike,red\n\
+                    1,x\n\
+                    9,a,b,官服,日常,\n\
+                    0,a,b,nowhere,日常,\n\
+                    2,a,b,官服,日常,\n\
+                    3,c,d,official,recruit,";
+        let import = import(text, &account).unwrap();
+        assert_eq!(import.errors.len(), 5, "{:?}", import.errors);
+        assert!(import.errors[0].contains("color"));
+        assert_eq!(import.rows.len(), 2);
+
+        let row = &import.rows[0];
+        assert_eq!((row.line, row.idx, row.overwrite), (2, 2, true));
+        assert_eq!(row.changed, vec!["username", "password", "server", "mode"]);
+        assert_eq!(row.account.server, Server::Bilibili);
+        assert_eq!(row.account.mode, AccountMode::ZL);
+        // columns not in the file keep their values
+        assert_eq!(row.account.fight, "ce-6");
+        assert_eq!(import.rows[1].account.mode, AccountMode::Recruit);
+
+        assert!(super::import("username\na", &account).is_err());
+        assert!(super::import("", &account).is_err());
+    }
+}
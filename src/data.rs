@@ -1,9 +1,13 @@
+use std::ops::RangeInclusive;
+
 use chrono::{Local, Timelike};
 use derivative::Derivative;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, PartialEq, Default, Clone)]
+use crate::i18n::{t, Lang};
+
+#[derive(Deserialize, Serialize, PartialEq, Default, Clone, Debug)]
 pub enum Server {
     #[default]
     Official,
@@ -20,14 +24,14 @@ impl Server {
     #[allow(dead_code)]
     pub fn str(self) -> String {
         match self {
-            Self::Official => "官服",
-            Self::Bilibili => "B服",
+            Self::Official => t("server.official"),
+            Self::Bilibili => t("server.bilibili"),
         }
         .into()
     }
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Default, Clone)]
+#[derive(Deserialize, Serialize, PartialEq, Default, Clone, Debug)]
 pub enum AccountMode {
     #[default]
     Daily,
@@ -45,15 +49,15 @@ impl AccountMode {
     }
     pub fn str(&self) -> String {
         match self {
-            Self::Daily => "日常",
-            Self::ZL => "肉鸽",
-            Self::Recruit => "公招",
+            Self::Daily => t("mode.daily"),
+            Self::ZL => t("mode.zl"),
+            Self::Recruit => t("mode.recruit"),
         }
         .into()
     }
 }
 
-#[derive(Builder, Deserialize, Serialize, Clone, Default)]
+#[derive(Builder, Deserialize, Serialize, PartialEq, Clone, Default, Debug)]
 #[serde(default)]
 #[builder(default)]
 pub struct Account {
@@ -148,7 +152,203 @@ pub struct Account {
     pub allow_after: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, Derivative)]
+impl Account {
+    /// Whether the account may run on each weekday, Monday first.
+    pub fn weekdays(&self) -> [bool; 7] {
+        [
+            self.allow_monday,
+            self.allow_tuesday,
+            self.allow_wednesday,
+            self.allow_thursday,
+            self.allow_friday,
+            self.allow_saturday,
+            self.allow_sunday,
+        ]
+    }
+
+    pub fn weekdays_mut(&mut self) -> [&mut bool; 7] {
+        [
+            &mut self.allow_monday,
+            &mut self.allow_tuesday,
+            &mut self.allow_wednesday,
+            &mut self.allow_thursday,
+            &mut self.allow_friday,
+            &mut self.allow_saturday,
+            &mut self.allow_sunday,
+        ]
+    }
+
+    /// Copies the settings shown in the daily card, the ones `inherit` refers to.
+    pub fn copy_daily(&mut self, from: &Account) {
+        self.fight = from.fight.clone();
+        self.max_drug = from.max_drug;
+        self.max_drug_day = from.max_drug_day.clone();
+        self.max_stone = from.max_stone;
+        self.prefer_goods = from.prefer_goods.clone();
+        self.dislike_goods = from.dislike_goods.clone();
+        self.recruit0 = from.recruit0;
+        self.recruit1 = from.recruit1;
+        self.recruit4 = from.recruit4;
+        self.recruit5 = from.recruit5;
+        self.recruit6 = from.recruit6;
+        self.job_mail = from.job_mail;
+        self.job_fight = from.job_fight;
+        self.job_friend = from.job_friend;
+        self.job_gain = from.job_gain;
+        self.job_shift = from.job_shift;
+        self.job_manu = from.job_manu;
+        self.job_clue = from.job_clue;
+        self.job_assist = from.job_assist;
+        self.job_shop = from.job_shop;
+        self.job_recruit = from.job_recruit;
+        self.job_task = from.job_task;
+        self.job_activity = from.job_activity;
+        self.allow_monday = from.allow_monday;
+        self.allow_tuesday = from.allow_tuesday;
+        self.allow_wednesday = from.allow_wednesday;
+        self.allow_thursday = from.allow_thursday;
+        self.allow_friday = from.allow_friday;
+        self.allow_saturday = from.allow_saturday;
+        self.allow_sunday = from.allow_sunday;
+        self.allow_after = from.allow_after.clone();
+    }
+
+    /// Takes every setting of `from` but where it inherits from, keeping this
+    /// account's username and password unless `credentials` is set.
+    pub fn paste(&mut self, from: &Account, credentials: bool) {
+        let mut x = from.clone();
+        x.inherit = self.inherit;
+        x.inherit_index = self.inherit_index;
+        if !credentials {
+            x.username = std::mem::take(&mut self.username);
+            x.password = std::mem::take(&mut self.password);
+        }
+        *self = x;
+    }
+
+    /// The fields that grant access to the account.
+    pub fn secrets(&self) -> [&str; 2] {
+        [&self.username, &self.password]
+    }
+
+    pub fn secrets_mut(&mut self) -> [&mut String; 2] {
+        [&mut self.username, &mut self.password]
+    }
+
+    /// The secrets a vault encrypts, the username stays readable so accounts
+    /// can be told apart.
+    pub fn vaulted(&self) -> [&str; 1] {
+        [&self.password]
+    }
+
+    pub fn vaulted_mut(&mut self) -> [&mut String; 1] {
+        [&mut self.password]
+    }
+}
+
+/// Number of account slots in a config.
+pub const TOTAL: usize = 10000;
+
+/// Values the editors allow for a numeric account field, with `total` slots.
+pub fn range(key: &str, total: usize) -> RangeInclusive<usize> {
+    match key {
+        "inherit_index" => 0..=total - 1,
+        "max_drug" | "max_stone" => 0..=99,
+        _ => 0..=9999,
+    }
+}
+
+/// A fresh account for slot `idx`: the first account has its own settings,
+/// the others inherit it.
+pub fn default_account(idx: usize) -> Account {
+    AccountBuilder::default().inherit(idx != 0).build().unwrap()
+}
+
+/// Whether `account` is what slot `idx` starts as. `allow_after` defaults to
+/// the day the app started, so it is ignored where the daily settings are
+/// inherited and the field is never read.
+pub fn is_default(account: &Account, idx: usize) -> bool {
+    let mut default = default_account(idx);
+    if default.inherit {
+        default.allow_after = account.allow_after.clone();
+    }
+    account == &default
+}
+
+fn remap_inherit(account: &mut [Account], map: impl Fn(usize) -> usize) {
+    for x in account {
+        x.inherit_index = map(x.inherit_index);
+    }
+}
+
+/// Swaps two slots, keeping `inherit_index` pointing at the same accounts.
+/// Returns where each old index went, for anything else keyed by index.
+pub fn swap(account: &mut [Account], a: usize, b: usize) -> impl Fn(usize) -> usize + Copy {
+    let map = move |i| {
+        if i == a {
+            b
+        } else if i == b {
+            a
+        } else {
+            i
+        }
+    };
+    account.swap(a, b);
+    remap_inherit(account, map);
+    map
+}
+
+/// Moves the account at `from` to `to`, shifting the slots in between, and
+/// keeps `inherit_index` pointing at the same accounts. Returns where each
+/// old index went.
+pub fn move_to(account: &mut [Account], from: usize, to: usize) -> impl Fn(usize) -> usize + Copy {
+    let map = move |i| {
+        if i == from {
+            to
+        } else if from < to && from < i && i <= to {
+            i - 1
+        } else if to < from && to <= i && i < from {
+            i + 1
+        } else {
+            i
+        }
+    };
+    if from < to {
+        account[from..=to].rotate_left(1);
+    } else {
+        account[to..=from].rotate_right(1);
+    }
+    remap_inherit(account, map);
+    map
+}
+
+/// Index of the account whose daily settings apply to `idx`, following `inherit`.
+pub fn daily_source(account: &[Account], mut idx: usize) -> usize {
+    for _ in 0..account.len() {
+        let x = &account[idx];
+        if x.mode != AccountMode::Daily
+            || !x.inherit
+            || x.inherit_index == idx
+            || x.inherit_index >= account.len()
+        {
+            break;
+        }
+        idx = x.inherit_index;
+    }
+    idx
+}
+
+/// The account at `idx` with inherited daily settings filled in.
+pub fn resolve(account: &[Account], idx: usize) -> Account {
+    let mut x = account[idx].clone();
+    let source = daily_source(account, idx);
+    if source != idx {
+        x.copy_daily(&account[source]);
+    }
+    x
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Derivative, Debug)]
 #[derivative(Default)]
 #[serde(default)]
 pub struct Setting {
@@ -176,6 +376,11 @@ pub struct Setting {
 
     #[derivative(Default(value = "\"4:00 12:00 20:00\".to_string()"))]
     pub crontab: String,
+
+    #[derivative(Default(value = "\"http://127.0.0.1:8611\".to_string()"))]
+    pub runner_url: String,
+
+    pub language: Lang,
 }
 
 impl Setting {
@@ -183,4 +388,136 @@ impl Setting {
         self.multi_account_choice = x.into();
         self
     }
+
+    /// The captcha and notify credentials.
+    pub fn secrets(&self) -> [&str; 4] {
+        [
+            &self.captcha_username,
+            &self.captcha_password,
+            &self.qq_notify,
+            &self.qq_notify_server,
+        ]
+    }
+
+    pub fn secrets_mut(&mut self) -> [&mut String; 4] {
+        [
+            &mut self.captcha_username,
+            &mut self.captcha_password,
+            &mut self.qq_notify,
+            &mut self.qq_notify_server,
+        ]
+    }
+
+    /// The secrets a vault encrypts, all but the captcha username.
+    pub fn vaulted(&self) -> [&str; 3] {
+        [
+            &self.captcha_password,
+            &self.qq_notify,
+            &self.qq_notify_server,
+        ]
+    }
+
+    pub fn vaulted_mut(&mut self) -> [&mut String; 3] {
+        [
+            &mut self.captcha_password,
+            &mut self.qq_notify,
+            &mut self.qq_notify_server,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(inherit: bool, inherit_index: usize, fight: &str) -> Account {
+        AccountBuilder::default()
+            .inherit(inherit)
+            .inherit_index(inherit_index)
+            .fight(fight.into())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn inherit_chain() {
+        let mut x = vec![
+            account(false, 0, "a"),
+            account(true, 0, "b"),
+            account(true, 1, "c"),
+            account(true, 4, "d"),
+            account(true, 3, "e"),
+            account(true, 99, "f"),
+        ];
+        assert_eq!(daily_source(&x, 0), 0);
+        assert_eq!(daily_source(&x, 2), 0);
+        assert_eq!(resolve(&x, 2).fight, "a");
+        // a cycle stops somewhere on the cycle instead of looping forever
+        assert!([3, 4].contains(&daily_source(&x, 3)));
+        assert_eq!(daily_source(&x, 5), 5);
+
+        x[1].mode = AccountMode::ZL;
+        assert_eq!(daily_source(&x, 2), 1);
+        assert_eq!(resolve(&x, 2).fight, "b");
+        assert_eq!(resolve(&x, 2).mode, AccountMode::Daily);
+    }
+
+    fn fights(x: &[Account]) -> Vec<&str> {
+        x.iter().map(|x| x.fight.as_str()).collect()
+    }
+
+    #[test]
+    fn swap_and_move() {
+        let mut x = vec![
+            account(false, 0, "a"),
+            account(true, 0, "b"),
+            account(true, 1, "c"),
+            account(true, 0, "d"),
+        ];
+        let map = swap(&mut x, 0, 1);
+        assert_eq!(fights(&x), ["b", "a", "c", "d"]);
+        assert_eq!((0..4).map(map).collect::<Vec<_>>(), [1, 0, 2, 3]);
+        assert_eq!(resolve(&x, 2).fight, "a");
+        assert_eq!(resolve(&x, 3).fight, "a");
+        assert_eq!(x[0].inherit_index, 1);
+
+        let map = move_to(&mut x, 1, 3);
+        assert_eq!(fights(&x), ["b", "c", "d", "a"]);
+        assert_eq!((0..4).map(map).collect::<Vec<_>>(), [0, 3, 1, 2]);
+        assert_eq!(resolve(&x, 0).fight, "a");
+        assert_eq!(resolve(&x, 1).fight, "a");
+        assert_eq!(resolve(&x, 2).fight, "a");
+
+        let map = move_to(&mut x, 3, 0);
+        assert_eq!(fights(&x), ["a", "b", "c", "d"]);
+        assert_eq!((0..4).map(map).collect::<Vec<_>>(), [1, 2, 3, 0]);
+        assert_eq!(
+            x.iter().map(|x| x.inherit_index).collect::<Vec<_>>(),
+            [0, 0, 1, 0]
+        );
+        let map = move_to(&mut x, 2, 2);
+        assert_eq!(fights(&x), ["a", "b", "c", "d"]);
+        assert_eq!((0..4).map(map).collect::<Vec<_>>(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn paste() {
+        let mut x = account(false, 0, "a");
+        x.username = "user".into();
+        x.password = "secret".into();
+        let mut y = account(true, 3, "b");
+        y.username = "other".into();
+
+        y.paste(&x, false);
+        assert_eq!((y.fight.as_str(), y.username.as_str()), ("a", "other"));
+        assert_eq!(y.password, "");
+        // where the account inherits from is its own
+        assert!(y.inherit);
+        assert_eq!(y.inherit_index, 3);
+        y.paste(&x, true);
+        assert_eq!(
+            (y.username.as_str(), y.password.as_str()),
+            ("user", "secret")
+        );
+    }
 }
@@ -0,0 +1,122 @@
+use poll_promise::Promise;
+
+/// Saves `text` as a file named `name`.
+///
+/// Native builds write into the working directory and return the path,
+/// the web build hands the file to the browser as a download.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_text(name: &str, text: &str) -> Result<String, String> {
+    let path = std::env::current_dir()
+        .map_err(|e| e.to_string())?
+        .join(name);
+    std::fs::write(&path, text).map_err(|e| e.to_string())?;
+    Ok(path.display().to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_text(name: &str, text: &str) -> Result<String, String> {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    fn f(name: &str, text: &str) -> Result<(), JsValue> {
+        let document = web_sys::window()
+            .and_then(|x| x.document())
+            .ok_or("no document")?;
+        let parts = js_sys::Array::of1(&JsValue::from_str(text));
+        let mut options = web_sys::BlobPropertyBag::new();
+        options.type_("text/plain;charset=utf-8");
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+        let a: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+        a.set_href(&url);
+        a.set_download(name);
+        a.click();
+        web_sys::Url::revoke_object_url(&url)
+    }
+    f(name, text).map_err(|e| format!("{e:?}"))?;
+    Ok(name.to_string())
+}
+
+/// The name and text of a file being opened, `None` when the user closed the
+/// picker without choosing one.
+pub type Opened = Promise<Option<Result<(String, String), String>>>;
+
+/// Writes `text` to `path` and returns the path written.
+///
+/// The web build cannot choose where files go, so it downloads the file under
+/// the last component of `path` instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_as(path: &str, text: &str) -> Result<String, String> {
+    std::fs::write(path, text).map_err(|e| format!("{path}: {e}"))?;
+    Ok(path.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_as(path: &str, text: &str) -> Result<String, String> {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    save_text(name, text)
+}
+
+/// Reads the file at `path`.
+///
+/// The web build ignores `path` and lets the user upload a file instead, which
+/// finishes once they picked one.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open(path: &str) -> Opened {
+    let result = std::fs::read_to_string(path)
+        .map(|text| (path.to_string(), text))
+        .map_err(|e| format!("{path}: {e}"));
+    Promise::from_ready(Some(result))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn open(_path: &str) -> Opened {
+    use std::{cell::RefCell, rc::Rc};
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+    let (sender, promise) = Promise::new();
+    // whichever of `change` and `cancel` fires first resolves the promise
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let f = move || -> Result<(), JsValue> {
+        let document = web_sys::window()
+            .and_then(|x| x.document())
+            .ok_or("no document")?;
+        let input: web_sys::HtmlInputElement = document.create_element("input")?.dyn_into()?;
+        input.set_type("file");
+        input.set_accept(".json,application/json");
+        let picked = input.clone();
+        let changed = sender.clone();
+        let onchange = Closure::once(move || {
+            let Some(sender) = changed.borrow_mut().take() else {
+                return;
+            };
+            let Some(file) = picked.files().and_then(|x| x.get(0)) else {
+                sender.send(None);
+                return;
+            };
+            wasm_bindgen_futures::spawn_local(async move {
+                let text = wasm_bindgen_futures::JsFuture::from(file.text()).await;
+                let result = match text.map(|x| x.as_string()) {
+                    Ok(Some(text)) => Ok((file.name(), text)),
+                    Ok(None) => Err("not text".to_string()),
+                    Err(e) => Err(format!("{e:?}")),
+                };
+                sender.send(Some(result));
+            });
+        });
+        input.set_onchange(Some(onchange.as_ref().unchecked_ref()));
+        onchange.forget();
+        let oncancel = Closure::once(move || {
+            if let Some(sender) = sender.borrow_mut().take() {
+                sender.send(None);
+            }
+        });
+        input.add_event_listener_with_callback("cancel", oncancel.as_ref().unchecked_ref())?;
+        oncancel.forget();
+        input.click();
+        Ok(())
+    };
+    match f() {
+        Ok(()) => promise,
+        Err(e) => Promise::from_ready(Some(Err(format!("{e:?}")))),
+    }
+}
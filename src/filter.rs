@@ -0,0 +1,142 @@
+use crate::choice::Choice;
+use crate::data::{Account, AccountMode, Server};
+
+/// Conditions narrowing the accounts shown in multi-account mode, all of
+/// which must hold. `None` means any.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct AccountFilter {
+    /// Substring of the username, case-insensitive.
+    pub username: String,
+    pub server: Option<Server>,
+    pub mode: Option<AccountMode>,
+    /// Whether the account is in `multi_account_choice`.
+    pub enabled: Option<bool>,
+    /// Only daily accounts inheriting this account.
+    pub inherit: Option<usize>,
+    pub non_empty: bool,
+}
+
+impl AccountFilter {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether `account` passes, with `query` the lowercased username
+    /// filter and `lower` the account's lowercased username.
+    fn matches(
+        &self,
+        account: &Account,
+        idx: usize,
+        choice: &Choice,
+        query: &str,
+        lower: &str,
+    ) -> bool {
+        (query.is_empty() || lower.contains(query))
+            && self.server.iter().all(|x| x == &account.server)
+            && self.mode.iter().all(|x| x == &account.mode)
+            && self.enabled.iter().all(|&x| x == choice.contains(idx))
+            && self.inherit.iter().all(|&x| {
+                account.mode == AccountMode::Daily && account.inherit && account.inherit_index == x
+            })
+            && (!self.non_empty || !account.username.is_empty())
+    }
+
+    /// Indices of the matching accounts, ascending. `usernames` are the
+    /// accounts' usernames as [`lowercase`] gives them.
+    pub fn apply(&self, account: &[Account], usernames: &[String], choice: &Choice) -> Vec<usize> {
+        let query = self.username.trim().to_lowercase();
+        account
+            .iter()
+            .zip(usernames)
+            .enumerate()
+            .filter(|(i, (x, lower))| self.matches(x, *i, choice, &query, lower))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// The usernames lowercased, kept between filter passes so the accounts are
+/// not lowercased again every frame.
+pub fn lowercase(account: &[Account]) -> Vec<String> {
+    account.iter().map(|x| x.username.to_lowercase()).collect()
+}
+
+/// Row of `rows` showing account `idx`, or the first row after it when it is
+/// filtered out.
+pub fn row_of(rows: &[usize], idx: usize) -> usize {
+    rows.iter()
+        .position(|&i| i == idx)
+        .or_else(|| rows.iter().position(|&i| i > idx))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::choice;
+    use crate::data::AccountBuilder;
+
+    #[test]
+    fn filter() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 6];
+        account[0].username = "Alice".into();
+        account[0].inherit = false;
+        account[1].username = "bob".into();
+        account[1].server = Server::Bilibili;
+        account[2].username = "alina".into();
+        account[2].mode = AccountMode::ZL;
+        account[3].inherit_index = 2;
+        let choice = choice::parse("0-2").unwrap();
+        let lower = lowercase(&account);
+
+        let all = AccountFilter::default();
+        assert!(all.is_empty());
+        assert_eq!(all.apply(&account, &lower, &choice).len(), 6);
+
+        let filter = AccountFilter {
+            username: " AL ".into(),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&account, &lower, &choice), vec![0, 2]);
+
+        let filter = AccountFilter {
+            server: Some(Server::Bilibili),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&account, &lower, &choice), vec![1]);
+
+        let filter = AccountFilter {
+            mode: Some(AccountMode::Daily),
+            enabled: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&account, &lower, &choice), vec![0, 1]);
+
+        let filter = AccountFilter {
+            enabled: Some(false),
+            non_empty: true,
+            ..Default::default()
+        };
+        assert!(filter.apply(&account, &lower, &choice).is_empty());
+
+        // account 0 does not inherit and account 2 is not daily
+        let filter = AccountFilter {
+            inherit: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&account, &lower, &choice), vec![1, 4, 5]);
+        let filter = AccountFilter {
+            inherit: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&account, &lower, &choice), vec![3]);
+    }
+
+    #[test]
+    fn rows() {
+        assert_eq!(row_of(&[1, 4, 5], 4), 1);
+        assert_eq!(row_of(&[1, 4, 5], 2), 1);
+        assert_eq!(row_of(&[1, 4, 5], 9), 0);
+        assert_eq!(row_of(&[5, 1, 4], 1), 1);
+    }
+}
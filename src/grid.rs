@@ -0,0 +1,165 @@
+use std::cmp::{Ordering, Reverse};
+
+use crate::data::{Account, AccountMode, Server};
+use crate::i18n::t;
+
+/// Columns of the compact account grid.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Column {
+    Index,
+    Username,
+    Server,
+    Mode,
+    Inherit,
+    Fight,
+    MaxDrug,
+    MaxStone,
+    Weekday,
+}
+
+impl Column {
+    pub const ALL: [Column; 9] = [
+        Self::Index,
+        Self::Username,
+        Self::Server,
+        Self::Mode,
+        Self::Inherit,
+        Self::Fight,
+        Self::MaxDrug,
+        Self::MaxStone,
+        Self::Weekday,
+    ];
+
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Index => "#",
+            Self::Username => t("field.username"),
+            Self::Server => t("field.server"),
+            Self::Mode => t("field.mode"),
+            Self::Inherit => t("inherit.on"),
+            Self::Fight => t("field.fight"),
+            Self::MaxDrug => t("field.max_drug"),
+            Self::MaxStone => t("field.max_stone"),
+            Self::Weekday => t("field.time"),
+        }
+    }
+
+    /// Help key of the field shown in the column.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Index => "multi_account_choice",
+            Self::Username => "username",
+            Self::Server => "server",
+            Self::Mode => "mode",
+            Self::Inherit => "inherit",
+            Self::Fight => "fight",
+            Self::MaxDrug => "max_drug",
+            Self::MaxStone => "max_stone",
+            Self::Weekday => "allow_monday",
+        }
+    }
+
+    fn cmp(&self, a: &Account, b: &Account) -> Ordering {
+        fn server(x: &Server) -> u8 {
+            match x {
+                Server::Official => 0,
+                Server::Bilibili => 1,
+            }
+        }
+        fn mode(x: &AccountMode) -> u8 {
+            match x {
+                AccountMode::Daily => 0,
+                AccountMode::ZL => 1,
+                AccountMode::Recruit => 2,
+            }
+        }
+        // accounts with their own settings first, then by inherited account
+        fn inherit(x: &Account) -> (bool, usize) {
+            (x.inherit, if x.inherit { x.inherit_index } else { 0 })
+        }
+        fn weekday(x: &Account) -> u8 {
+            x.weekdays()
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| (x as u8) << (6 - i))
+                .sum()
+        }
+        match self {
+            Self::Index => Ordering::Equal,
+            Self::Username => a.username.to_lowercase().cmp(&b.username.to_lowercase()),
+            Self::Server => server(&a.server).cmp(&server(&b.server)),
+            Self::Mode => mode(&a.mode).cmp(&mode(&b.mode)),
+            Self::Inherit => inherit(a).cmp(&inherit(b)),
+            Self::Fight => a.fight.cmp(&b.fight),
+            Self::MaxDrug => a.max_drug.cmp(&b.max_drug),
+            Self::MaxStone => a.max_stone.cmp(&b.max_stone),
+            Self::Weekday => weekday(a).cmp(&weekday(b)),
+        }
+    }
+}
+
+/// Sorts account indices by `column`, ties keep index order either way.
+pub fn sort(account: &[Account], rows: &mut [usize], column: Column, descending: bool) {
+    if column == Column::Username {
+        // lowercase each name once rather than at every comparison
+        let name = |i: usize| account[i].username.to_lowercase();
+        if descending {
+            rows.sort_by_cached_key(|&i| (Reverse(name(i)), i));
+        } else {
+            rows.sort_by_cached_key(|&i| (name(i), i));
+        }
+        return;
+    }
+    rows.sort_by(|&a, &b| {
+        let order = match column {
+            Column::Index => a.cmp(&b),
+            _ => column.cmp(&account[a], &account[b]),
+        };
+        let order = if descending { order.reverse() } else { order };
+        order.then(a.cmp(&b))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountBuilder;
+
+    #[test]
+    fn sort_columns() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 4];
+        account[0].username = "b".into();
+        account[0].inherit = false;
+        account[1].username = "A".into();
+        account[1].max_drug = 3;
+        account[2].username = "c".into();
+        account[2].inherit_index = 1;
+        account[3].allow_monday = false;
+        account[3].max_drug = 3;
+
+        let mut rows = vec![0, 1, 2, 3];
+        sort(&account, &mut rows, Column::Username, false);
+        assert_eq!(rows, vec![3, 1, 0, 2]);
+        sort(&account, &mut rows, Column::Username, true);
+        assert_eq!(rows, vec![2, 0, 1, 3]);
+        sort(&account, &mut rows, Column::Username, false);
+        // equal keys stay in index order when descending too
+        sort(&account, &mut rows, Column::MaxDrug, true);
+        assert_eq!(rows, vec![1, 3, 0, 2]);
+        sort(&account, &mut rows, Column::Inherit, false);
+        assert_eq!(rows, vec![0, 1, 3, 2]);
+        sort(&account, &mut rows, Column::Weekday, false);
+        assert_eq!(rows, vec![3, 0, 1, 2]);
+        sort(&account, &mut rows, Column::Index, true);
+        assert_eq!(rows, vec![3, 2, 1, 0]);
+
+        let mut rows = vec![2, 0];
+        sort(&account, &mut rows, Column::Index, false);
+        assert_eq!(rows, vec![0, 2]);
+
+        account[3].username = "a".into();
+        let mut rows = vec![3, 2, 1, 0];
+        sort(&account, &mut rows, Column::Username, true);
+        assert_eq!(rows, vec![2, 0, 1, 3]);
+    }
+}
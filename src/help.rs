@@ -0,0 +1,173 @@
+use crate::i18n::{self, t, Lang};
+
+/// Documentation of one `Account` or `Setting` field, keyed by its serde name.
+pub struct Entry {
+    pub key: &'static str,
+    /// Catalogue keys of the field's name and description.
+    label: &'static str,
+    text: &'static str,
+}
+
+impl Entry {
+    pub fn label(&self) -> &'static str {
+        t(self.label)
+    }
+
+    pub fn text(&self) -> &'static str {
+        t(self.text)
+    }
+
+    /// Whether `name` is the key or the label in either language, ignoring case.
+    pub fn named(&self, name: &str) -> bool {
+        let name = name.trim();
+        self.key.eq_ignore_ascii_case(name)
+            || Lang::ALL
+                .iter()
+                .any(|&lang| i18n::tr(lang, self.label).eq_ignore_ascii_case(name))
+    }
+
+    /// Searches both languages, so either works whatever the interface shows.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.key.contains(&query)
+            || Lang::ALL.iter().any(|&lang| {
+                i18n::tr(lang, self.label).to_lowercase().contains(&query)
+                    || i18n::tr(lang, self.text).to_lowercase().contains(&query)
+            })
+    }
+}
+
+/// The entry of `key`, its texts under `help.label.<key>` and
+/// `help.text.<key>` in the catalogues.
+macro_rules! entry {
+    ($key:literal) => {
+        Entry {
+            key: $key,
+            label: concat!("help.label.", $key),
+            text: concat!("help.text.", $key),
+        }
+    };
+}
+
+pub const ACCOUNT: &[Entry] = &[
+    entry!("username"),
+    entry!("password"),
+    entry!("server"),
+    entry!("mode"),
+    entry!("inherit"),
+    entry!("inherit_index"),
+    entry!("fight"),
+    entry!("max_drug"),
+    entry!("max_drug_day"),
+    entry!("max_stone"),
+    entry!("prefer_goods"),
+    entry!("dislike_goods"),
+    entry!("recruit0"),
+    entry!("recruit1"),
+    entry!("recruit4"),
+    entry!("recruit5"),
+    entry!("recruit6"),
+    entry!("recruit_recruit1"),
+    entry!("recruit_recruit4"),
+    entry!("recruit_recruit5"),
+    entry!("recruit_recruit6"),
+    entry!("job_mail"),
+    entry!("job_fight"),
+    entry!("job_friend"),
+    entry!("job_gain"),
+    entry!("job_shift"),
+    entry!("job_manu"),
+    entry!("job_clue"),
+    entry!("job_assist"),
+    entry!("job_shop"),
+    entry!("job_recruit"),
+    entry!("job_task"),
+    entry!("job_activity"),
+    entry!("allow_monday"),
+    entry!("allow_tuesday"),
+    entry!("allow_wednesday"),
+    entry!("allow_thursday"),
+    entry!("allow_friday"),
+    entry!("allow_saturday"),
+    entry!("allow_sunday"),
+    entry!("allow_after"),
+    entry!("zl_level"),
+    entry!("zl_max_level"),
+    entry!("zl_coin"),
+    entry!("zl_max_coin"),
+    entry!("zl_no_waste"),
+];
+
+pub const SETTING: &[Entry] = &[
+    entry!("multi_account"),
+    entry!("multi_account_choice"),
+    entry!("multi_account_clue"),
+    entry!("multi_account_allow_empty"),
+    entry!("captcha_username"),
+    entry!("captcha_password"),
+    entry!("max_fight_failed_times"),
+    entry!("max_login_times_15min"),
+    entry!("qq_notify"),
+    entry!("qq_notify_server"),
+    entry!("qq_notify_mail"),
+    entry!("qq_notify_dorm_enter"),
+    entry!("qq_notify_dorm_leave"),
+    entry!("qq_notify_task"),
+    entry!("crontab"),
+    entry!("runner_url"),
+    entry!("language"),
+];
+
+pub fn get(key: &str) -> Option<&'static Entry> {
+    ACCOUNT.iter().chain(SETTING).find(|x| x.key == key)
+}
+
+pub fn text(key: &str) -> &'static str {
+    get(key).map(|x| x.text()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Account, Setting};
+
+    fn keys<T: serde::Serialize>(x: &T) -> Vec<String> {
+        match serde_json::to_value(x).unwrap() {
+            serde_json::Value::Object(x) => x.keys().cloned().collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn every_field_documented() {
+        for key in keys(&Account::default()) {
+            assert!(ACCOUNT.iter().any(|x| x.key == key), "no help for {key}");
+        }
+        for key in keys(&Setting::default()) {
+            assert!(SETTING.iter().any(|x| x.key == key), "no help for {key}");
+        }
+        assert_eq!(ACCOUNT.len(), keys(&Account::default()).len());
+        assert_eq!(SETTING.len(), keys(&Setting::default()).len());
+    }
+
+    #[test]
+    fn every_entry_translated() {
+        for x in ACCOUNT.iter().chain(SETTING) {
+            for lang in Lang::ALL {
+                assert_ne!(i18n::tr(lang, x.label), x.label, "no label for {}", x.key);
+                assert_ne!(i18n::tr(lang, x.text), x.text, "no text for {}", x.key);
+            }
+        }
+    }
+
+    #[test]
+    fn search() {
+        assert!(get("fight").unwrap().matches("剿灭"));
+        assert!(get("fight").unwrap().matches("FIGHT"));
+        assert!(get("fight").unwrap().matches("annihilation"));
+        assert!(get("fight").unwrap().matches(" "));
+        assert!(!get("fight").unwrap().matches("通知"));
+        assert_eq!(text("nothing"), "");
+    }
+}
@@ -0,0 +1,949 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub enum Lang {
+    #[default]
+    Zh,
+    En,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Self::Zh, Self::En];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Zh => "中文",
+            Self::En => "English",
+        }
+    }
+}
+
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the language used by [`t`] and [`tf`], once per frame from the setting.
+pub fn set(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::Zh,
+    }
+}
+
+fn catalogue(lang: Lang) -> &'static HashMap<&'static str, &'static str> {
+    static ZH_MAP: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+    static EN_MAP: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+    match lang {
+        Lang::Zh => ZH_MAP.get_or_init(|| ZH.iter().copied().collect()),
+        Lang::En => EN_MAP.get_or_init(|| EN.iter().copied().collect()),
+    }
+}
+
+/// The message for `key` in `lang`, falling back to Chinese and then the key.
+pub fn tr(lang: Lang, key: &'static str) -> &'static str {
+    catalogue(lang)
+        .get(key)
+        .or_else(|| catalogue(Lang::Zh).get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+pub fn t(key: &'static str) -> &'static str {
+    tr(current(), key)
+}
+
+/// [`t`] with `{0}`, `{1}`, ... replaced by `args`.
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut text = t(key).to_string();
+    for (i, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{i}}}"), &arg.to_string());
+    }
+    text
+}
+
+const ZH: &[(&str, &str)] = &[
+    ("section.account", "账号"),
+    ("section.setting", "设置"),
+    ("search", "搜索"),
+    ("field.username", "账号"),
+    ("field.password", "密码"),
+    ("field.server", "服务"),
+    ("field.mode", "模式"),
+    ("field.fight", "关卡"),
+    ("field.max_drug", "吃药"),
+    ("field.max_stone", "石头"),
+    ("field.max_drug_day", "到期"),
+    ("field.prefer_goods", "多买"),
+    ("field.dislike_goods", "少买"),
+    ("field.recruit", "招募"),
+    ("field.jobs", "任务"),
+    ("field.time", "时间"),
+    ("field.allow_after", "起"),
+    ("server.official", "官服"),
+    ("server.bilibili", "B服"),
+    ("mode.daily", "日常"),
+    ("mode.zl", "肉鸽"),
+    ("mode.recruit", "公招"),
+    ("login.fail", "无效"),
+    ("login.success", "有效"),
+    ("login.unknown", "未知"),
+    ("inherit.on", "继承"),
+    ("inherit.off", "独立"),
+    ("inherit.prefix", "账号"),
+    ("unit.times", "次"),
+    ("unit.count", "个"),
+    ("unit.days", "{0}天"),
+    ("zl.level", "等级(蜡烛)"),
+    ("zl.coin", "源石锭"),
+    ("zl.no_waste", "先做日常"),
+    ("recruit.other", "其他"),
+    ("recruit.robot", "小车"),
+    ("recruit.star4", "四星"),
+    ("recruit.star5", "五星"),
+    ("recruit.star6", "六星"),
+    ("job.mail", "邮件"),
+    ("job.fight", "作战"),
+    ("job.friend", "好友"),
+    ("job.gain", "收菜"),
+    ("job.shift", "换班"),
+    ("job.manu", "加速"),
+    ("job.clue", "线索"),
+    ("job.assist", "副手"),
+    ("job.shop", "信交"),
+    ("job.recruit", "公招"),
+    ("job.task", "任务"),
+    ("job.activity", "活动"),
+    ("weekday.mon", "周一"),
+    ("weekday.tue", "周二"),
+    ("weekday.wed", "周三"),
+    ("weekday.thu", "周四"),
+    ("weekday.fri", "周五"),
+    ("weekday.sat", "周六"),
+    ("weekday.sun", "周日"),
+    ("setting.captcha_username", "图鉴账号"),
+    ("setting.captcha_password", "图鉴密码"),
+    ("setting.max_fight_failed", "同一关卡连续导航或代理失败出现"),
+    ("setting.max_fight_failed_after", "后跳过"),
+    ("setting.max_login", "同一账号登录界面15分钟内出现"),
+    ("setting.max_login_after", "后跳过"),
+    ("setting.qq_notify", "通知账号"),
+    ("setting.qq_notify_server", "通知服务"),
+    ("setting.notify_scene", "通知场景"),
+    ("notify.mail", "邮件前"),
+    ("notify.dorm_enter", "进基建"),
+    ("notify.dorm_leave", "出基建"),
+    ("notify.task", "任务前"),
+    ("setting.multi_account", "多号模式"),
+    ("setting.multi_account_clue", "多号线索账号"),
+    ("setting.multi_account_allow_empty", "多号不跳过空白账号"),
+    ("setting.crontab", "定时重启"),
+    ("setting.runner_url", "运行服务"),
+    ("setting.language", "语言"),
+    ("button.test", "测试"),
+    ("button.preview", "预览"),
+    ("button.clear", "清除"),
+    ("button.export", "导出"),
+    ("button.import", "导入"),
+    ("button.refresh", "刷新"),
+    ("button.start", "启动"),
+    ("button.stop", "停止"),
+    ("button.setting", "设置"),
+    ("button.log", "日志"),
+    ("button.help", "帮助"),
+    ("button.schedule", "计划"),
+    ("button.transfer", "导入导出"),
+    ("bottom.jump", "跳转至"),
+    ("bottom.choice", "启用"),
+    ("filter.all", "全部"),
+    ("filter.non_empty", "非空"),
+    ("filter.enabled", "已启用"),
+    ("filter.disabled", "未启用"),
+    ("filter.inherit", "继承自"),
+    ("view.grid", "表格"),
+    ("view.card", "卡片"),
+    ("bulk.select", "选择"),
+    ("bulk.toggle", "选中账号，按住Shift连续选择"),
+    ("bulk.selected", "已选 {0} 个"),
+    ("bulk.edit", "批量编辑"),
+    ("bulk.changes", "将修改 {0}/{1} 个账号"),
+    ("bulk.apply", "应用"),
+    ("bulk.applied", "已修改 {0} 个账号"),
+    ("slot.copy", "复制设置"),
+    ("slot.paste", "粘贴设置"),
+    ("slot.reset", "恢复默认"),
+    ("slot.credentials", "包括账号密码"),
+    ("slot.swap", "交换"),
+    ("slot.move", "移动至"),
+    ("shortcut.next", "下一个账号"),
+    ("shortcut.prev", "上一个账号"),
+    ("shortcut.jump", "跳转至账号"),
+    ("shortcut.setting", "打开或关闭设置"),
+    ("shortcut.save", "保存配置"),
+    ("shortcut.undo", "撤销"),
+    ("shortcut.redo", "重做"),
+    ("shortcut.cheat_sheet", "快捷键"),
+    ("shortcut.focus", "在账号的各个字段间移动"),
+    ("shortcut.bad_index", "无效的账号序号"),
+    ("validate.phone", "官服账号应为11位手机号"),
+    ("validate.no_password", "未填写密码"),
+    ("validate.no_account", "账号{0}不存在"),
+    ("validate.cycle", "继承关系成环"),
+    ("validate.broken_chain", "继承的账号本身的继承无效"),
+    ("validate.bad_stage", "无效的关卡 \"{0}\""),
+    ("validate.no_stage", "开启作战但未设置关卡"),
+    ("validate.bad_index", "无效的账号序号 \"{0}\""),
+    ("validate.qq", "QQ号应为5到11位数字"),
+    ("validate.url", "地址应以 http:// 或 https:// 开头"),
+    ("problems.title", "启动前检查"),
+    ("problems.start_anyway", "仍然启动"),
+    ("transfer.title", "导入导出账号和设置"),
+    ("transfer.script", "脚本配置"),
+    ("transfer.csv", "CSV账号表"),
+    ("transfer.paste", "粘贴配置后导入, 未提及的字段恢复默认"),
+    ("toast.imported_config", "已导入配置"),
+    ("toast.imported_accounts", "已导入 {0} 个账号"),
+    ("config.no_value", "缺少\"=\": \"{0}\""),
+    ("config.unknown", "未知字段 \"{0}\""),
+    ("config.bad_value", "字段 {0} 的值 \"{1}\" 无效"),
+    ("csv.no_index", "表头缺少序号列"),
+    ("file.current", "当前配置文件"),
+    ("file.path", "文件路径"),
+    ("file.open", "打开"),
+    ("file.save", "保存"),
+    ("file.save_as", "另存为"),
+    ("toast.saved", "已保存 {0}"),
+    ("toast.opened", "已打开 {0}"),
+    ("share.create", "生成分享链接"),
+    ("share.copy", "复制"),
+    ("share.paste", "粘贴分享码或链接"),
+    ("share.preview", "预览导入"),
+    ("share.title", "导入分享的配置"),
+    (
+        "share.keeps_credentials",
+        "分享不含账号密码和通知凭据, 本地已填写的保持不变",
+    ),
+    ("share.invalid", "无效的分享码"),
+    ("qr.button", "二维码"),
+    ("qr.title", "扫码导入到手机"),
+    ("qr.page", "第{0}/{1}张"),
+    ("qr.hint", "内容较多, 分成多张二维码, 依次扫描后按编号拼接"),
+    (
+        "vault.hint",
+        "设置口令后, 保存和导出的配置中密码和通知凭据将被加密",
+    ),
+    ("vault.passphrase", "口令"),
+    ("vault.confirm", "确认口令"),
+    ("vault.enable", "启用加密"),
+    ("vault.mismatch", "两次输入的口令不一致"),
+    (
+        "vault.enabled",
+        "已启用加密, 保存和导出的配置中密码和通知凭据已加密",
+    ),
+    ("vault.export_plain", "导出时解密"),
+    ("vault.disable", "停用加密"),
+    ("vault.unlock", "解锁"),
+    ("vault.later", "稍后"),
+    ("vault.locked", "配置中的密码已加密, 输入口令解锁"),
+    ("vault.wrong", "口令错误"),
+    ("vault.invalid", "无效的加密内容"),
+    ("toast.unlocked", "已解锁"),
+    ("validate.locked", "已加密, 请先解锁"),
+    ("secret.reveal", "显示"),
+    ("secret.hide_all", "隐藏所有账号和密码"),
+    ("json.account", "账号{0}"),
+    ("json.setting", "设置"),
+    ("json.all", "整个配置"),
+    ("json.title", "以JSON编辑{0}"),
+    ("json.edit", "JSON"),
+    ("json.edit_all", "以JSON编辑整个配置"),
+    ("json.error_at", "第{0}行第{1}列: {2}"),
+    ("json.error_in", "{0}: {1}"),
+    ("json.not_object", "应为对象"),
+    ("json.changes", "{0}处改动"),
+    ("json.no_changes", "没有改动"),
+    (
+        "json.hint",
+        "未写出的字段取默认值; 整个配置中未列出的账号恢复默认",
+    ),
+    ("json.apply", "应用"),
+    ("json.reload", "重新载入"),
+    (
+        "csv.paste",
+        "粘贴CSV后导入, 首行为表头且需含序号列, 表中没有的列保持原值",
+    ),
+    ("csv.preview", "将写入{0}个账号, 其中{1}个覆盖已有账号"),
+    ("csv.apply", "写入账号"),
+    ("csv.discard", "放弃"),
+    ("csv.overwrite", "覆盖"),
+    ("csv.unchanged", "无变化"),
+    ("csv.unknown_column", "未知列 \"{0}\", 已忽略"),
+    ("csv.cells", "有{0}列, 表头有{1}列"),
+    ("csv.duplicate", "账号{0}重复, 已跳过"),
+    ("history.ok", "成功 {0}"),
+    ("history.failed", "失败 {0}"),
+    ("history.none", "未运行"),
+    ("history.empty", "暂无记录"),
+    ("preview.rest", "当天不运行"),
+    ("preview.empty", "没有任务"),
+    ("log.account", "账号"),
+    ("log.follow", "自动滚动"),
+    (
+        "cli.usage",
+        "用法: mizuki_ui <命令> [参数]\n\n\
+  validate <文件>                      检查配置, 有错误时退出码为 1\n\
+  list <文件> [选择]                   列出账号, 默认列出已配置的账号\n\
+  set <文件> <选择> <字段=值>...       修改所选账号的字段, setting.<字段> 修改设置\n\
+  check <文件> [选择]                  验证账号密码, 默认使用多账号选择\n\
+  convert <输入> <输出>                在 json、csv 和 script 格式之间转换\n\
+  help                                 显示本说明\n\n\
+选项:\n\
+  --format <格式>    输入文件的格式, 默认按扩展名判断\n\
+  --from/--to <格式> 转换的输入/输出格式\n\
+  -o, --output <文件> set 的输出文件, 默认覆盖输入\n\
+  --show-secrets     list 显示完整的用户名\n\
+文件为 - 时使用标准输入/输出. 加密的配置需在环境变量 MIZUKI_PASSPHRASE 中提供口令.",
+    ),
+    (
+        "cli.unknown_format",
+        "无法识别格式 \"{0}\", 可用 json、csv 或 script",
+    ),
+    ("cli.unknown_option", "未知选项 \"{0}\""),
+    ("cli.no_option_value", "选项 {0} 缺少值"),
+    ("cli.csv_accounts", "CSV 只包含账号, 设置未写入"),
+    ("cli.summary", "{0}个错误, {1}个警告"),
+    ("cli.set", "已修改{0}个账号"),
+    ("cli.locked", "配置已加密, 请在环境变量 {0} 中提供口令"),
+    ("log.issue_report", "导出问题报告"),
+    (
+        "log.issue_report_hint",
+        "打包日志、配置和检查结果, 账号密码等已脱敏, 可直接附在反馈中",
+    ),
+    ("log.import_reports", "导入报告"),
+    ("log.debug", "调试"),
+    ("log.info", "信息"),
+    ("log.warn", "警告"),
+    ("log.error", "错误"),
+    ("toast.exported", "已导出 {0}"),
+    ("toast.imported_reports", "已导入 {0} 条报告"),
+    ("schedule.title", "未来7天各次定时重启处理的账号"),
+    ("schedule.empty", "未设置定时重启"),
+    ("schedule.count", "{0}个"),
+    ("schedule.bad_time", "无效的时间 \"{0}\""),
+    ("schedule.bad_date", "无效的日期 \"{0}\""),
+    ("choice.bad_start", "无效的起始账号 \"#{0}\""),
+    ("choice.bad_range", "无效的账号范围 \"{0}\""),
+    ("run.idle", "空闲"),
+    ("run.starting", "启动中"),
+    ("run.running", "运行中"),
+    ("run.stopped", "已停止"),
+    ("run.error", "错误: {0}"),
+    ("report.summary", "{0} 任务{1}个 理智{2} 关卡 {3}"),
+    ("report.failures", " 失败: {0}"),
+    ("report.bad_line", "第{0}行: {1}"),
+    ("step.fight", "作战 {0} 吃药{1}次 石头{2}次"),
+    ("step.no_stage", "无开放关卡"),
+    ("step.drug_day", " {0}天到期{1}个"),
+    ("step.shop", "信交 多买[{0}] 少买[{1}]"),
+    ("step.recruit", "公招 {0}"),
+    ("step.zl", "肉鸽"),
+    ("step.zl_level", " 等级至{0}"),
+    ("step.zl_coin", " 源石锭至{0}"),
+    ("help.label.username", "账号"),
+    ("help.text.username", "登录游戏的账号，官服为手机号。"),
+    ("help.label.password", "密码"),
+    ("help.text.password", "登录游戏的密码，点击“测试”可验证账号密码是否有效。"),
+    ("help.label.server", "服务"),
+    ("help.text.server", "账号所在服务器，官服或B服。"),
+    ("help.label.mode", "模式"),
+    ("help.text.mode", "日常：完成勾选的日常任务；肉鸽：刷集成战略；公招：只做公开招募。"),
+    ("help.label.inherit", "继承"),
+    ("help.text.inherit", "继承时日常设置（关卡、吃药、任务、时间等）使用被继承账号的设置，独立时使用本账号自己的设置。"),
+    ("help.label.inherit_index", "继承账号"),
+    ("help.text.inherit_index", "被继承的账号序号，可以继承一个本身也在继承的账号。"),
+    ("help.label.fight", "关卡"),
+    ("help.text.fight", "依次尝试的关卡，空格分隔，如 jm hd ce-6 ls-6 ap-5 pr-a-2。jm为剿灭，hd为活动，资源关只在开放日尝试。"),
+    ("help.label.max_drug", "吃药"),
+    ("help.text.max_drug", "每次运行最多使用理智药的次数。"),
+    ("help.label.max_drug_day", "到期"),
+    ("help.text.max_drug_day", "对即将过期的理智药额外使用的个数，依次为剩余6天到0天过期的药。"),
+    ("help.label.max_stone", "石头"),
+    ("help.text.max_stone", "每次运行最多碎源石恢复理智的次数。"),
+    ("help.label.prefer_goods", "多买"),
+    ("help.text.prefer_goods", "信用交易所优先购买的商品，空格分隔。"),
+    ("help.label.dislike_goods", "少买"),
+    ("help.text.dislike_goods", "信用交易所不购买的商品，空格分隔。"),
+    ("help.label.recruit0", "招募其他"),
+    ("help.text.recruit0", "日常公招时招募除小车和高星以外的其他标签。"),
+    ("help.label.recruit1", "招募小车"),
+    ("help.text.recruit1", "日常公招时招募支援机械（小车）标签。"),
+    ("help.label.recruit4", "招募四星"),
+    ("help.text.recruit4", "日常公招时招募可出四星的标签。"),
+    ("help.label.recruit5", "招募五星"),
+    ("help.text.recruit5", "日常公招时招募可出五星的标签。"),
+    ("help.label.recruit6", "招募六星"),
+    ("help.text.recruit6", "日常公招时招募可出六星的标签。"),
+    ("help.label.recruit_recruit1", "公招模式小车"),
+    ("help.text.recruit_recruit1", "公招模式下招募支援机械（小车）标签。"),
+    ("help.label.recruit_recruit4", "公招模式四星"),
+    ("help.text.recruit_recruit4", "公招模式下招募可出四星的标签。"),
+    ("help.label.recruit_recruit5", "公招模式五星"),
+    ("help.text.recruit_recruit5", "公招模式下招募可出五星的标签。"),
+    ("help.label.recruit_recruit6", "公招模式六星"),
+    ("help.text.recruit_recruit6", "公招模式下招募可出六星的标签。"),
+    ("help.label.job_mail", "邮件"),
+    ("help.text.job_mail", "领取邮件。"),
+    ("help.label.job_fight", "作战"),
+    ("help.text.job_fight", "按“关卡”设置刷关卡，关闭后关卡和吃药设置不生效。"),
+    ("help.label.job_friend", "好友"),
+    ("help.text.job_friend", "访问好友基建获取信用。"),
+    ("help.label.job_gain", "收菜"),
+    ("help.text.job_gain", "收取基建的产物和信赖。"),
+    ("help.label.job_shift", "换班"),
+    ("help.text.job_shift", "基建干员换班。"),
+    ("help.label.job_manu", "加速"),
+    ("help.text.job_manu", "使用无人机加速制造站。"),
+    ("help.label.job_clue", "线索"),
+    ("help.text.job_clue", "会客室线索收取、赠送和交流。"),
+    ("help.label.job_assist", "副手"),
+    ("help.text.job_assist", "更换助战干员。"),
+    ("help.label.job_shop", "信交"),
+    ("help.text.job_shop", "在信用交易所购物，关闭后多买少买不生效。"),
+    ("help.label.job_recruit", "公招"),
+    ("help.text.job_recruit", "日常公开招募，关闭后招募设置不生效。"),
+    ("help.label.job_task", "任务"),
+    ("help.text.job_task", "领取日常和周常任务奖励。"),
+    ("help.label.job_activity", "活动"),
+    ("help.text.job_activity", "领取活动签到等奖励。"),
+    ("help.label.allow_monday", "周一"),
+    ("help.text.allow_monday", "允许在周一运行。"),
+    ("help.label.allow_tuesday", "周二"),
+    ("help.text.allow_tuesday", "允许在周二运行。"),
+    ("help.label.allow_wednesday", "周三"),
+    ("help.text.allow_wednesday", "允许在周三运行。"),
+    ("help.label.allow_thursday", "周四"),
+    ("help.text.allow_thursday", "允许在周四运行。"),
+    ("help.label.allow_friday", "周五"),
+    ("help.text.allow_friday", "允许在周五运行。"),
+    ("help.label.allow_saturday", "周六"),
+    ("help.text.allow_saturday", "允许在周六运行。"),
+    ("help.label.allow_sunday", "周日"),
+    ("help.text.allow_sunday", "允许在周日运行。"),
+    ("help.label.allow_after", "起"),
+    ("help.text.allow_after", "在该时间之前不运行，格式为 2022-12-01 04:00。"),
+    ("help.label.zl_level", "等级(蜡烛)"),
+    ("help.text.zl_level", "肉鸽模式下刷等级（蜡烛）。"),
+    ("help.label.zl_max_level", "等级上限"),
+    ("help.text.zl_max_level", "肉鸽等级达到该值后不再刷等级。"),
+    ("help.label.zl_coin", "源石锭"),
+    ("help.text.zl_coin", "肉鸽模式下刷源石锭。"),
+    ("help.label.zl_max_coin", "源石锭上限"),
+    ("help.text.zl_max_coin", "源石锭达到该数量后不再刷源石锭。"),
+    ("help.label.zl_no_waste", "先做日常"),
+    ("help.text.zl_no_waste", "刷肉鸽前先完成日常任务，避免浪费理智。"),
+    ("help.label.multi_account", "多号模式"),
+    ("help.text.multi_account", "依次运行多个账号，关闭时只运行第一个账号。"),
+    ("help.label.multi_account_choice", "启用"),
+    ("help.text.multi_account_choice", "参与运行的账号序号，如 0-9 20 30-35，以空格或逗号分隔；末尾的 #n 表示从第n号账号开始。"),
+    ("help.label.multi_account_clue", "多号线索账号"),
+    ("help.text.multi_account_clue", "多号模式下负责线索交流的账号序号。"),
+    ("help.label.multi_account_allow_empty", "多号不跳过空白账号"),
+    ("help.text.multi_account_allow_empty", "开启时没有填写账号的序号也会运行，使用游戏内当前登录的账号。"),
+    ("help.label.captcha_username", "图鉴账号"),
+    ("help.text.captcha_username", "图鉴打码平台账号，用于识别登录验证码。"),
+    ("help.label.captcha_password", "图鉴密码"),
+    ("help.text.captcha_password", "图鉴打码平台密码。"),
+    ("help.label.max_fight_failed_times", "作战失败次数"),
+    ("help.text.max_fight_failed_times", "同一关卡连续导航或代理失败达到该次数后跳过该关卡。"),
+    ("help.label.max_login_times_15min", "登录次数"),
+    ("help.text.max_login_times_15min", "同一账号15分钟内出现登录界面达到该次数后跳过该账号。"),
+    ("help.label.qq_notify", "通知账号"),
+    ("help.text.qq_notify", "接收通知的QQ号。"),
+    ("help.label.qq_notify_server", "通知服务"),
+    ("help.text.qq_notify_server", "发送QQ通知的服务地址。"),
+    ("help.label.qq_notify_mail", "邮件前"),
+    ("help.text.qq_notify_mail", "领取邮件前发送通知。"),
+    ("help.label.qq_notify_dorm_enter", "进基建"),
+    ("help.text.qq_notify_dorm_enter", "进入基建时发送通知。"),
+    ("help.label.qq_notify_dorm_leave", "出基建"),
+    ("help.text.qq_notify_dorm_leave", "离开基建时发送通知。"),
+    ("help.label.qq_notify_task", "任务前"),
+    ("help.text.qq_notify_task", "领取任务前发送通知。"),
+    ("help.label.crontab", "定时重启"),
+    ("help.text.crontab", "每天重启脚本的时间，空格分隔，如 4:00 12:00 20:00。"),
+    ("help.label.runner_url", "运行服务"),
+    ("help.text.runner_url", "Mizuki运行服务的地址，点击“启动”时配置发送到这里。"),
+    ("help.label.language", "语言"),
+    ("help.text.language", "界面显示的语言。"),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("section.account", "Accounts"),
+    ("section.setting", "Settings"),
+    ("search", "Search"),
+    ("field.username", "Account"),
+    ("field.password", "Password"),
+    ("field.server", "Server"),
+    ("field.mode", "Mode"),
+    ("field.fight", "Stages"),
+    ("field.max_drug", "Potions"),
+    ("field.max_stone", "Originite"),
+    ("field.max_drug_day", "Expiring"),
+    ("field.prefer_goods", "Buy"),
+    ("field.dislike_goods", "Avoid"),
+    ("field.recruit", "Recruit"),
+    ("field.jobs", "Tasks"),
+    ("field.time", "Days"),
+    ("field.allow_after", "onward"),
+    ("server.official", "Official"),
+    ("server.bilibili", "Bilibili"),
+    ("mode.daily", "Daily"),
+    ("mode.zl", "Roguelike"),
+    ("mode.recruit", "Recruit"),
+    ("login.fail", "Invalid"),
+    ("login.success", "Valid"),
+    ("login.unknown", "Unknown"),
+    ("inherit.on", "Inherit"),
+    ("inherit.off", "Own"),
+    ("inherit.prefix", "account "),
+    ("unit.times", "x"),
+    ("unit.count", ""),
+    ("unit.days", "{0}d"),
+    ("zl.level", "Level (candles)"),
+    ("zl.coin", "Ingots"),
+    ("zl.no_waste", "Daily first"),
+    ("recruit.other", "Other"),
+    ("recruit.robot", "Robot"),
+    ("recruit.star4", "4★"),
+    ("recruit.star5", "5★"),
+    ("recruit.star6", "6★"),
+    ("job.mail", "Mail"),
+    ("job.fight", "Fight"),
+    ("job.friend", "Friends"),
+    ("job.gain", "Collect"),
+    ("job.shift", "Shift"),
+    ("job.manu", "Boost"),
+    ("job.clue", "Clues"),
+    ("job.assist", "Support"),
+    ("job.shop", "Shop"),
+    ("job.recruit", "Recruit"),
+    ("job.task", "Missions"),
+    ("job.activity", "Event"),
+    ("weekday.mon", "Mon"),
+    ("weekday.tue", "Tue"),
+    ("weekday.wed", "Wed"),
+    ("weekday.thu", "Thu"),
+    ("weekday.fri", "Fri"),
+    ("weekday.sat", "Sat"),
+    ("weekday.sun", "Sun"),
+    ("setting.captcha_username", "Captcha account"),
+    ("setting.captcha_password", "Captcha password"),
+    (
+        "setting.max_fight_failed",
+        "Skip a stage after navigation or auto-deploy fails",
+    ),
+    ("setting.max_fight_failed_after", "in a row"),
+    (
+        "setting.max_login",
+        "Skip an account after the login screen shows",
+    ),
+    ("setting.max_login_after", "within 15 minutes"),
+    ("setting.qq_notify", "Notify QQ"),
+    ("setting.qq_notify_server", "Notify server"),
+    ("setting.notify_scene", "Notify on"),
+    ("notify.mail", "Mail"),
+    ("notify.dorm_enter", "Base enter"),
+    ("notify.dorm_leave", "Base leave"),
+    ("notify.task", "Missions"),
+    ("setting.multi_account", "Multiple accounts"),
+    ("setting.multi_account_clue", "Clue account"),
+    ("setting.multi_account_allow_empty", "Run blank accounts"),
+    ("setting.crontab", "Restart at"),
+    ("setting.runner_url", "Runner"),
+    ("setting.language", "Language"),
+    ("button.test", "Test"),
+    ("button.preview", "Preview"),
+    ("button.clear", "Clear"),
+    ("button.export", "Export"),
+    ("button.import", "Import"),
+    ("button.refresh", "Refresh"),
+    ("button.start", "Start"),
+    ("button.stop", "Stop"),
+    ("button.setting", "Settings"),
+    ("button.log", "Log"),
+    ("button.help", "Help"),
+    ("button.schedule", "Schedule"),
+    ("button.transfer", "Transfer"),
+    ("bottom.jump", "Go to "),
+    ("bottom.choice", "Enabled"),
+    ("filter.all", "All"),
+    ("filter.non_empty", "Non-empty"),
+    ("filter.enabled", "Enabled"),
+    ("filter.disabled", "Disabled"),
+    ("filter.inherit", "Inherits"),
+    ("view.grid", "Grid"),
+    ("view.card", "Cards"),
+    ("bulk.select", "Select"),
+    (
+        "bulk.toggle",
+        "Select the account, hold Shift to select a range",
+    ),
+    ("bulk.selected", "{0} selected"),
+    ("bulk.edit", "Bulk edit"),
+    ("bulk.changes", "Changes {0} of {1} accounts"),
+    ("bulk.apply", "Apply"),
+    ("bulk.applied", "Changed {0} accounts"),
+    ("slot.copy", "Copy settings"),
+    ("slot.paste", "Paste settings"),
+    ("slot.reset", "Reset to default"),
+    ("slot.credentials", "Include credentials"),
+    ("slot.swap", "Swap"),
+    ("slot.move", "Move to"),
+    ("shortcut.next", "Next account"),
+    ("shortcut.prev", "Previous account"),
+    ("shortcut.jump", "Go to account"),
+    ("shortcut.setting", "Open or close settings"),
+    ("shortcut.save", "Save configuration"),
+    ("shortcut.undo", "Undo"),
+    ("shortcut.redo", "Redo"),
+    ("shortcut.cheat_sheet", "Shortcuts"),
+    ("shortcut.focus", "Move between the fields of an account"),
+    ("shortcut.bad_index", "Invalid account index"),
+    (
+        "validate.phone",
+        "Official accounts are 11-digit phone numbers",
+    ),
+    ("validate.no_password", "Password is empty"),
+    ("validate.no_account", "Account {0} does not exist"),
+    ("validate.cycle", "Inheritance forms a cycle"),
+    ("validate.broken_chain", "Inherits from an account whose inherit is invalid"),
+    ("validate.bad_stage", "Invalid stage \"{0}\""),
+    ("validate.no_stage", "Fight is on but no stage is set"),
+    ("validate.bad_index", "Invalid account index \"{0}\""),
+    ("validate.qq", "QQ numbers have 5 to 11 digits"),
+    ("validate.url", "Addresses start with http:// or https://"),
+    ("problems.title", "Pre-run check"),
+    ("problems.start_anyway", "Start anyway"),
+    ("transfer.title", "Import and export accounts and settings"),
+    ("transfer.script", "Script config"),
+    ("transfer.csv", "CSV accounts"),
+    (
+        "transfer.paste",
+        "Paste a config and import it, fields not mentioned are reset",
+    ),
+    ("toast.imported_config", "Config imported"),
+    ("toast.imported_accounts", "Imported {0} accounts"),
+    ("config.no_value", "missing \"=\": \"{0}\""),
+    ("config.unknown", "unknown field \"{0}\""),
+    ("config.bad_value", "invalid value \"{1}\" for {0}"),
+    ("csv.no_index", "the header has no index column"),
+    ("file.current", "Current config file"),
+    ("file.path", "File path"),
+    ("file.open", "Open"),
+    ("file.save", "Save"),
+    ("file.save_as", "Save as"),
+    ("toast.saved", "Saved {0}"),
+    ("toast.opened", "Opened {0}"),
+    ("share.create", "Create share link"),
+    ("share.copy", "Copy"),
+    ("share.paste", "Paste a share code or link"),
+    ("share.preview", "Preview import"),
+    ("share.title", "Import shared config"),
+    ("share.keeps_credentials", "Shares carry no accounts, passwords or notify credentials, the ones entered here are kept"),
+    ("share.invalid", "Invalid share code"),
+    ("qr.button", "QR code"),
+    ("qr.title", "Scan into the phone"),
+    ("qr.page", "{0} of {1}"),
+    ("qr.hint", "Split into several codes, scan them all and join them by number"),
+    ("vault.hint", "With a passphrase set, passwords and notify credentials are encrypted in saved and exported configs"),
+    ("vault.passphrase", "Passphrase"),
+    ("vault.confirm", "Confirm passphrase"),
+    ("vault.enable", "Enable encryption"),
+    ("vault.mismatch", "The passphrases differ"),
+    ("vault.enabled", "Encryption on, passwords and notify credentials are encrypted in saved and exported configs"),
+    ("vault.export_plain", "Export decrypted"),
+    ("vault.disable", "Disable encryption"),
+    ("vault.unlock", "Unlock"),
+    ("vault.later", "Later"),
+    ("vault.locked", "Passwords in the config are encrypted, enter the passphrase to unlock"),
+    ("vault.wrong", "Wrong passphrase"),
+    ("vault.invalid", "Invalid encrypted value"),
+    ("toast.unlocked", "Unlocked"),
+    ("validate.locked", "encrypted, unlock first"),
+    ("secret.reveal", "Show"),
+    ("secret.hide_all", "Hide all accounts and passwords"),
+    ("json.account", "account {0}"),
+    ("json.setting", "settings"),
+    ("json.all", "the whole config"),
+    ("json.title", "Edit {0} as JSON"),
+    ("json.edit", "JSON"),
+    ("json.edit_all", "Edit the whole config as JSON"),
+    ("json.error_at", "line {0} column {1}: {2}"),
+    ("json.error_in", "{0}: {1}"),
+    ("json.not_object", "expected an object"),
+    ("json.changes", "{0} changes"),
+    ("json.no_changes", "No changes"),
+    ("json.hint", "Fields left out take their defaults; accounts not listed in the whole config are reset"),
+    ("json.apply", "Apply"),
+    ("json.reload", "Reload"),
+    ("csv.paste", "Paste a CSV and import it, the header needs an index column and columns left out keep their values"),
+    ("csv.preview", "{0} accounts will be written, {1} of them overwrite configured accounts"),
+    ("csv.apply", "Write accounts"),
+    ("csv.discard", "Discard"),
+    ("csv.overwrite", "overwrite"),
+    ("csv.unchanged", "unchanged"),
+    ("csv.unknown_column", "unknown column \"{0}\", ignored"),
+    ("csv.cells", "{0} cells but the header has {1}"),
+    ("csv.duplicate", "account {0} repeated, skipped"),
+    ("history.ok", "OK {0}"),
+    ("history.failed", "Failed {0}"),
+    ("history.none", "Never run"),
+    ("history.empty", "No records"),
+    ("preview.rest", "Does not run that day"),
+    ("preview.empty", "No tasks"),
+    ("log.account", "Account"),
+    ("log.follow", "Auto scroll"),
+    ("cli.usage", "Usage: mizuki_ui <command> [arguments]\n\n\
+  validate <file>                      check the config, exit code 1 on errors\n\
+  list <file> [selection]              list accounts, the configured ones by default\n\
+  set <file> <selection> <field=value>...  set fields of the selected accounts, setting.<field> for settings\n\
+  check <file> [selection]             test account credentials, the multi-account selection by default\n\
+  convert <input> <output>             convert between the json, csv and script formats\n\
+  help                                 show this text\n\n\
+Options:\n\
+  --format <format>    format of the input file, by extension otherwise\n\
+  --from/--to <format> input/output format of a conversion\n\
+  -o, --output <file>  where set writes to, the input by default\n\
+  --show-secrets       list full usernames instead of masked ones\n\
+A file named - is standard input/output. Encrypted configs need their passphrase in MIZUKI_PASSPHRASE."),
+    ("cli.unknown_format", "unknown format \"{0}\", use json, csv or script"),
+    ("cli.unknown_option", "unknown option \"{0}\""),
+    ("cli.no_option_value", "option {0} needs a value"),
+    ("cli.csv_accounts", "CSV holds accounts only, the setting is not written"),
+    ("cli.summary", "{0} errors, {1} warnings"),
+    ("cli.set", "changed {0} accounts"),
+    ("cli.locked", "the config is encrypted, set its passphrase in {0}"),
+    ("log.issue_report", "Export issue report"),
+    ("log.issue_report_hint", "Bundles the log, config and validation results with credentials redacted, ready to attach to a bug report"),
+    ("log.import_reports", "Import reports"),
+    ("log.debug", "debug"),
+    ("log.info", "info"),
+    ("log.warn", "warn"),
+    ("log.error", "error"),
+    ("toast.exported", "Exported {0}"),
+    ("toast.imported_reports", "Imported {0} reports"),
+    (
+        "schedule.title",
+        "Accounts processed at each restart in the next 7 days",
+    ),
+    ("schedule.empty", "No restart times set"),
+    ("schedule.count", "{0} accounts"),
+    ("schedule.bad_time", "Invalid time \"{0}\""),
+    ("schedule.bad_date", "Invalid date \"{0}\""),
+    ("choice.bad_start", "Invalid start account \"#{0}\""),
+    ("choice.bad_range", "Invalid account range \"{0}\""),
+    ("run.idle", "Idle"),
+    ("run.starting", "Starting"),
+    ("run.running", "Running"),
+    ("run.stopped", "Stopped"),
+    ("run.error", "Error: {0}"),
+    ("report.summary", "{0} {1} tasks, {2} sanity, stages {3}"),
+    ("report.failures", ", failed: {0}"),
+    ("report.bad_line", "line {0}: {1}"),
+    ("step.fight", "Fight {0}, {1} potions, {2} originite"),
+    ("step.no_stage", "no open stage"),
+    ("step.drug_day", ", {1} expiring in {0}d"),
+    ("step.shop", "Shop, buy [{0}] avoid [{1}]"),
+    ("step.recruit", "Recruit {0}"),
+    ("step.zl", "Roguelike"),
+    ("step.zl_level", ", level up to {0}"),
+    ("step.zl_coin", ", ingots up to {0}"),
+    ("help.label.username", "Account"),
+    ("help.text.username", "Game login account, the phone number on the official server."),
+    ("help.label.password", "Password"),
+    ("help.text.password", "Game login password, click \"Test\" to check the account."),
+    ("help.label.server", "Server"),
+    ("help.text.server", "Server of the account, official or Bilibili."),
+    ("help.label.mode", "Mode"),
+    ("help.text.mode", "Daily: run the checked daily tasks; Roguelike: farm Integrated Strategies; Recruit: only do recruitment."),
+    ("help.label.inherit", "Inherit"),
+    ("help.text.inherit", "When inheriting, daily settings (stages, potions, tasks, days...) come from the inherited account, otherwise from this account."),
+    ("help.label.inherit_index", "Inherited account"),
+    ("help.text.inherit_index", "Index of the inherited account, which may itself inherit another account."),
+    ("help.label.fight", "Stages"),
+    ("help.text.fight", "Stages tried in order, separated by spaces, e.g. jm hd ce-6 ls-6 ap-5 pr-a-2. jm is Annihilation, hd is the event, resource stages are only tried on open days."),
+    ("help.label.max_drug", "Potions"),
+    ("help.text.max_drug", "Maximum sanity potions used per run."),
+    ("help.label.max_drug_day", "Expiring"),
+    ("help.text.max_drug_day", "Extra potions used when about to expire, for potions expiring in 6 down to 0 days."),
+    ("help.label.max_stone", "Originite"),
+    ("help.text.max_stone", "Maximum originite prime used to restore sanity per run."),
+    ("help.label.prefer_goods", "Buy"),
+    ("help.text.prefer_goods", "Goods preferred in the credit store, separated by spaces."),
+    ("help.label.dislike_goods", "Avoid"),
+    ("help.text.dislike_goods", "Goods never bought in the credit store, separated by spaces."),
+    ("help.label.recruit0", "Recruit other"),
+    ("help.text.recruit0", "Recruit tags other than Robot and high rarity in daily recruitment."),
+    ("help.label.recruit1", "Recruit robot"),
+    ("help.text.recruit1", "Recruit the Robot tag in daily recruitment."),
+    ("help.label.recruit4", "Recruit 4★"),
+    ("help.text.recruit4", "Recruit tags that may give 4★ operators in daily recruitment."),
+    ("help.label.recruit5", "Recruit 5★"),
+    ("help.text.recruit5", "Recruit tags that may give 5★ operators in daily recruitment."),
+    ("help.label.recruit6", "Recruit 6★"),
+    ("help.text.recruit6", "Recruit tags that may give 6★ operators in daily recruitment."),
+    ("help.label.recruit_recruit1", "Recruit mode robot"),
+    ("help.text.recruit_recruit1", "Recruit the Robot tag in recruit mode."),
+    ("help.label.recruit_recruit4", "Recruit mode 4★"),
+    ("help.text.recruit_recruit4", "Recruit tags that may give 4★ operators in recruit mode."),
+    ("help.label.recruit_recruit5", "Recruit mode 5★"),
+    ("help.text.recruit_recruit5", "Recruit tags that may give 5★ operators in recruit mode."),
+    ("help.label.recruit_recruit6", "Recruit mode 6★"),
+    ("help.text.recruit_recruit6", "Recruit tags that may give 6★ operators in recruit mode."),
+    ("help.label.job_mail", "Mail"),
+    ("help.text.job_mail", "Collect mail."),
+    ("help.label.job_fight", "Fight"),
+    ("help.text.job_fight", "Fight the stages in \"Stages\", stage and potion settings are ignored when off."),
+    ("help.label.job_friend", "Friends"),
+    ("help.text.job_friend", "Visit friends' bases for credit."),
+    ("help.label.job_gain", "Collect"),
+    ("help.text.job_gain", "Collect base products and trust."),
+    ("help.label.job_shift", "Shift"),
+    ("help.text.job_shift", "Rotate base operators."),
+    ("help.label.job_manu", "Boost"),
+    ("help.text.job_manu", "Use drones on factories."),
+    ("help.label.job_clue", "Clues"),
+    ("help.text.job_clue", "Collect, give and exchange clues in the reception room."),
+    ("help.label.job_assist", "Support"),
+    ("help.text.job_assist", "Change the support operator."),
+    ("help.label.job_shop", "Shop"),
+    ("help.text.job_shop", "Shop in the credit store, buy and avoid settings are ignored when off."),
+    ("help.label.job_recruit", "Recruit"),
+    ("help.text.job_recruit", "Daily recruitment, recruit settings are ignored when off."),
+    ("help.label.job_task", "Missions"),
+    ("help.text.job_task", "Claim daily and weekly mission rewards."),
+    ("help.label.job_activity", "Event"),
+    ("help.text.job_activity", "Claim event check-in rewards."),
+    ("help.label.allow_monday", "Mon"),
+    ("help.text.allow_monday", "Allow running on Monday."),
+    ("help.label.allow_tuesday", "Tue"),
+    ("help.text.allow_tuesday", "Allow running on Tuesday."),
+    ("help.label.allow_wednesday", "Wed"),
+    ("help.text.allow_wednesday", "Allow running on Wednesday."),
+    ("help.label.allow_thursday", "Thu"),
+    ("help.text.allow_thursday", "Allow running on Thursday."),
+    ("help.label.allow_friday", "Fri"),
+    ("help.text.allow_friday", "Allow running on Friday."),
+    ("help.label.allow_saturday", "Sat"),
+    ("help.text.allow_saturday", "Allow running on Saturday."),
+    ("help.label.allow_sunday", "Sun"),
+    ("help.text.allow_sunday", "Allow running on Sunday."),
+    ("help.label.allow_after", "Onward"),
+    ("help.text.allow_after", "Do not run before this time, formatted like 2022-12-01 04:00."),
+    ("help.label.zl_level", "Level (candles)"),
+    ("help.text.zl_level", "Farm levels (candles) in roguelike mode."),
+    ("help.label.zl_max_level", "Level limit"),
+    ("help.text.zl_max_level", "Stop farming levels at this level."),
+    ("help.label.zl_coin", "Ingots"),
+    ("help.text.zl_coin", "Farm originium ingots in roguelike mode."),
+    ("help.label.zl_max_coin", "Ingot limit"),
+    ("help.text.zl_max_coin", "Stop farming ingots at this amount."),
+    ("help.label.zl_no_waste", "Daily first"),
+    ("help.text.zl_no_waste", "Finish daily tasks before the roguelike so no sanity is wasted."),
+    ("help.label.multi_account", "Multiple accounts"),
+    ("help.text.multi_account", "Run several accounts in turn, only the first account runs when off."),
+    ("help.label.multi_account_choice", "Enabled"),
+    ("help.text.multi_account_choice", "Indices of the accounts that run, e.g. 0-9 20 30-35, separated by spaces or commas; a trailing #n starts from account n."),
+    ("help.label.multi_account_clue", "Clue account"),
+    ("help.text.multi_account_clue", "Index of the account that exchanges clues in multi-account mode."),
+    ("help.label.multi_account_allow_empty", "Run blank accounts"),
+    ("help.text.multi_account_allow_empty", "When on, indices without an account also run, using the account currently logged in."),
+    ("help.label.captcha_username", "Captcha account"),
+    ("help.text.captcha_username", "Account of the captcha solving service, used for login captchas."),
+    ("help.label.captcha_password", "Captcha password"),
+    ("help.text.captcha_password", "Password of the captcha solving service."),
+    ("help.label.max_fight_failed_times", "Fight failures"),
+    ("help.text.max_fight_failed_times", "Skip a stage after navigation or auto-deploy fails this many times in a row."),
+    ("help.label.max_login_times_15min", "Login times"),
+    ("help.text.max_login_times_15min", "Skip an account after its login screen shows this many times within 15 minutes."),
+    ("help.label.qq_notify", "Notify QQ"),
+    ("help.text.qq_notify", "QQ number receiving notifications."),
+    ("help.label.qq_notify_server", "Notify server"),
+    ("help.text.qq_notify_server", "Address of the service sending QQ notifications."),
+    ("help.label.qq_notify_mail", "Mail"),
+    ("help.text.qq_notify_mail", "Notify before collecting mail."),
+    ("help.label.qq_notify_dorm_enter", "Base enter"),
+    ("help.text.qq_notify_dorm_enter", "Notify when entering the base."),
+    ("help.label.qq_notify_dorm_leave", "Base leave"),
+    ("help.text.qq_notify_dorm_leave", "Notify when leaving the base."),
+    ("help.label.qq_notify_task", "Missions"),
+    ("help.text.qq_notify_task", "Notify before claiming missions."),
+    ("help.label.crontab", "Restart at"),
+    ("help.text.crontab", "Daily times the script restarts, separated by spaces, e.g. 4:00 12:00 20:00."),
+    ("help.label.runner_url", "Runner"),
+    ("help.text.runner_url", "Address of the Mizuki runner, the configuration is sent there on \"Start\"."),
+    ("help.label.language", "Language"),
+    ("help.text.language", "Language of the interface."),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn keys(catalogue: &[(&'static str, &str)]) -> HashSet<&'static str> {
+        let keys: HashSet<_> = catalogue.iter().map(|x| x.0).collect();
+        assert_eq!(keys.len(), catalogue.len(), "duplicated key");
+        keys
+    }
+
+    #[test]
+    fn catalogues_complete() {
+        let zh = keys(ZH);
+        let en = keys(EN);
+        let mut missing: Vec<_> = zh.symmetric_difference(&en).collect();
+        missing.sort();
+        assert!(missing.is_empty(), "missing in a catalogue: {missing:?}");
+    }
+
+    #[test]
+    fn used_keys_exist() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        let sources: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|x| std::fs::read_to_string(x.unwrap().path()).unwrap())
+            .collect();
+        let zh = keys(ZH);
+        for source in &sources {
+            for call in ["t(\"", "tf(\""] {
+                for (i, _) in source.match_indices(call) {
+                    let before = source[..i].chars().last().unwrap();
+                    if before.is_alphanumeric() || before == '_' {
+                        continue;
+                    }
+                    let key = source[i + call.len()..].split('"').next().unwrap();
+                    assert!(zh.contains(key), "unknown key {key}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn translate() {
+        assert_eq!(tr(Lang::Zh, "button.start"), "启动");
+        assert_eq!(tr(Lang::En, "button.start"), "Start");
+        assert_eq!(tr(Lang::En, "no.such.key"), "no.such.key");
+        assert_eq!(
+            tf("step.drug_day", &[&3, &9]),
+            tr(current(), "step.drug_day")
+                .replace("{0}", "3")
+                .replace("{1}", "9")
+        );
+    }
+}
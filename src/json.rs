@@ -0,0 +1,297 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::config::object;
+use crate::data::{default_account, is_default, Account, Setting};
+use crate::i18n::{t, tf};
+
+/// What a JSON document covers.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Scope {
+    Account(usize),
+    Setting,
+    /// The setting and every account.
+    All,
+}
+
+impl Scope {
+    pub fn str(&self) -> String {
+        match self {
+            Self::Account(idx) => tf("json.account", &[idx]),
+            Self::Setting => t("json.setting").into(),
+            Self::All => t("json.all").into(),
+        }
+    }
+}
+
+/// The document of `scope`. The whole config lists only the accounts that
+/// differ from their defaults, keyed by index, so it stays small enough to edit.
+pub fn document(scope: Scope, account: &[Account], setting: &Setting) -> Value {
+    match scope {
+        Scope::Account(idx) => Value::Object(object(&account[idx])),
+        Scope::Setting => Value::Object(object(setting)),
+        Scope::All => {
+            let account = account
+                .iter()
+                .enumerate()
+                .filter(|(idx, x)| !is_default(x, *idx))
+                .map(|(idx, x)| (idx.to_string(), Value::Object(object(x))))
+                .collect();
+            serde_json::json!({
+                "setting": object(setting),
+                "account": Value::Object(account),
+            })
+        }
+    }
+}
+
+fn object_at<'a>(value: &'a Value, path: &str) -> Result<&'a Map<String, Value>, String> {
+    value
+        .as_object()
+        .ok_or_else(|| tf("json.error_in", &[&path, &t("json.not_object")]))
+}
+
+/// Reads `value` as a `T`, taking missing fields from `like` and naming
+/// `path` and any field `T` does not have.
+fn check<T: serde::Serialize + DeserializeOwned>(
+    value: &Value,
+    like: &T,
+    path: &str,
+) -> Result<T, String> {
+    let mut merged = object(like);
+    let error = |e: String| tf("json.error_in", &[&path, &e]);
+    for (key, value) in object_at(value, path)? {
+        if !merged.contains_key(key) {
+            return Err(error(tf("config.unknown", &[key])));
+        }
+        merged.insert(key.clone(), value.clone());
+    }
+    serde_json::from_value(Value::Object(merged)).map_err(|e| error(e.to_string()))
+}
+
+/// Parses an edited document of `scope` into its normalized form, with every
+/// field filled in. Syntax errors carry their line and column.
+pub fn parse(scope: Scope, text: &str, total: usize) -> Result<Value, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| {
+        let message = e.to_string();
+        let message = match message.rsplit_once(" at line ") {
+            Some((x, _)) => x.to_string(),
+            None => message,
+        };
+        tf("json.error_at", &[&e.line(), &e.column(), &message])
+    })?;
+    match scope {
+        Scope::Account(idx) => {
+            let x = check(&value, &default_account(idx), &idx.to_string())?;
+            Ok(Value::Object(object(&x)))
+        }
+        Scope::Setting => {
+            let x = check(&value, &Setting::default(), "setting")?;
+            Ok(Value::Object(object(&x)))
+        }
+        Scope::All => {
+            let root = object_at(&value, "/")?;
+            let mut setting = Setting::default();
+            let mut account = vec![];
+            for (key, value) in root {
+                match key.as_str() {
+                    "setting" => setting = check(value, &setting, "setting")?,
+                    // files saved before the document was sparse list every account
+                    "account" if value.is_array() => {
+                        for (i, value) in value.as_array().unwrap().iter().enumerate() {
+                            if i >= total {
+                                return Err(tf("validate.bad_index", &[&i]));
+                            }
+                            let path = format!("account.{i}");
+                            account.push((i, check(value, &default_account(i), &path)?));
+                        }
+                    }
+                    "account" => {
+                        for (idx, value) in object_at(value, "account")? {
+                            let i = idx
+                                .parse()
+                                .ok()
+                                .filter(|&x| x < total)
+                                .ok_or_else(|| tf("validate.bad_index", &[idx]))?;
+                            let path = format!("account.{idx}");
+                            account.push((i, check(value, &default_account(i), &path)?));
+                        }
+                    }
+                    _ => return Err(tf("config.unknown", &[key])),
+                }
+            }
+            account.sort_by_key(|(idx, _)| *idx);
+            let account = account
+                .into_iter()
+                .map(|(idx, x)| (idx.to_string(), Value::Object(object(&x))))
+                .collect();
+            Ok(serde_json::json!({
+                "setting": object(&setting),
+                "account": Value::Object(account),
+            }))
+        }
+    }
+}
+
+/// One value an edit changes, values in compact JSON.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Change {
+    pub path: String,
+    pub before: String,
+    pub after: String,
+}
+
+fn diff_into(path: &str, before: &Value, after: &Value, out: &mut Vec<Change>) {
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<_> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => diff_into(&path, a, b, out),
+                    (a, b) => out.push(Change {
+                        path,
+                        before: a.map_or("-".into(), Value::to_string),
+                        after: b.map_or("-".into(), Value::to_string),
+                    }),
+                }
+            }
+        }
+        (a, b) if a != b => out.push(Change {
+            path: path.to_string(),
+            before: a.to_string(),
+            after: b.to_string(),
+        }),
+        _ => {}
+    }
+}
+
+/// What replacing the `before` document of `scope` with `after` changes.
+/// Accounts missing from a whole config count as defaults.
+pub fn diff(scope: Scope, before: &Value, after: &Value) -> Vec<Change> {
+    let mut out = vec![];
+    if scope != Scope::All {
+        diff_into("", before, after, &mut out);
+        return out;
+    }
+    diff_into("setting", &before["setting"], &after["setting"], &mut out);
+    let empty = Map::new();
+    let a = before["account"].as_object().unwrap_or(&empty);
+    let b = after["account"].as_object().unwrap_or(&empty);
+    let mut keys: Vec<usize> = a
+        .keys()
+        .chain(b.keys())
+        .filter_map(|x| x.parse().ok())
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    for idx in keys {
+        let default = Value::Object(object(&default_account(idx)));
+        let key = idx.to_string();
+        let path = format!("account.{idx}");
+        diff_into(
+            &path,
+            a.get(&key).unwrap_or(&default),
+            b.get(&key).unwrap_or(&default),
+            &mut out,
+        );
+    }
+    out
+}
+
+/// Writes a document returned by [`parse`] back into the configuration.
+pub fn apply(scope: Scope, document: &Value, account: &mut [Account], setting: &mut Setting) {
+    fn from<T: DeserializeOwned>(x: &Value) -> T {
+        serde_json::from_value(x.clone()).unwrap()
+    }
+    match scope {
+        Scope::Account(idx) => account[idx] = from(document),
+        Scope::Setting => *setting = from(document),
+        Scope::All => {
+            *setting = from(&document["setting"]);
+            for (idx, x) in account.iter_mut().enumerate() {
+                *x = match document["account"].get(idx.to_string()) {
+                    Some(value) => from(value),
+                    None => default_account(idx),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts() -> Vec<Account> {
+        let mut account: Vec<_> = (0..3).map(default_account).collect();
+        account[1].username = "a".into();
+        account
+    }
+
+    #[test]
+    fn round_trip() {
+        let account = accounts();
+        let setting = Setting::default();
+        for scope in [Scope::Account(1), Scope::Setting, Scope::All] {
+            let text = document(scope, &account, &setting).to_string();
+            let value = parse(scope, &text, 3).unwrap();
+            assert_eq!(value, document(scope, &account, &setting));
+            assert!(diff(scope, &value, &value).is_empty());
+        }
+        let all = document(Scope::All, &account, &setting);
+        assert_eq!(all["account"].as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn errors() {
+        let e = parse(Scope::Setting, "{\n  \"crontab\": 4\n}", 3).unwrap_err();
+        assert!(e.contains("setting"), "{e}");
+        let e = parse(Scope::Setting, "{\n  \"crontab\": \"4:00\",\n}", 3).unwrap_err();
+        assert!(e.contains('3') && e.contains('1'), "{e}");
+        assert!(!e.contains(" at line "), "{e}");
+        assert!(parse(Scope::Account(0), "{\"colour\": 1}", 3).is_err());
+        assert!(parse(Scope::Account(0), "[]", 3).is_err());
+        assert!(parse(Scope::All, "{\"account\": {\"3\": {}}}", 3).is_err());
+        assert!(parse(Scope::All, "{\"other\": 1}", 3).is_err());
+        let x = parse(Scope::All, "{\"account\": [{}, {\"fight\": \"1-7\"}]}", 3).unwrap();
+        assert_eq!(x["account"]["1"]["fight"], "1-7");
+        assert!(parse(Scope::All, "{\"account\": [{}, {}, {}, {}]}", 3).is_err());
+        // missing fields take their defaults
+        let x = parse(Scope::Account(2), "{\"fight\": \"1-7\"}", 3).unwrap();
+        assert_eq!(x["inherit"], Value::Bool(true));
+    }
+
+    #[test]
+    fn diff_and_apply() {
+        let mut account = accounts();
+        let mut setting = Setting::default();
+        let before = document(Scope::All, &account, &setting);
+        let text = r#"{"setting": {"crontab": "5:00"}, "account": {"2": {"fight": "1-7"}}}"#;
+        let after = parse(Scope::All, text, 3).unwrap();
+        let changes = diff(Scope::All, &before, &after);
+        let paths: Vec<_> = changes.iter().map(|x| x.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["setting.crontab", "account.1.username", "account.2.fight"]
+        );
+        assert_eq!(changes[0].after, "\"5:00\"");
+
+        apply(Scope::All, &after, &mut account, &mut setting);
+        assert_eq!(setting.crontab, "5:00");
+        assert_eq!(account[1], default_account(1));
+        assert_eq!(account[2].fight, "1-7");
+
+        let after = parse(Scope::Account(0), "{\"max_drug\": 2}", 3).unwrap();
+        let before = document(Scope::Account(0), &account, &setting);
+        assert_eq!(diff(Scope::Account(0), &before, &after).len(), 1);
+        apply(Scope::Account(0), &after, &mut account, &mut setting);
+        assert_eq!(account[0].max_drug, 2);
+    }
+}
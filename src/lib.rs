@@ -1,4 +1,31 @@
 mod api;
 mod data;
 mod app;
+mod runner;
+mod log;
+mod file;
+mod report;
+mod choice;
+mod schedule;
+mod plan;
+mod help;
+mod i18n;
+mod filter;
+mod grid;
+mod bulk;
+mod card;
+mod shortcut;
+mod undo;
+mod validate;
+mod config;
+mod csv;
+mod json;
+mod share;
+mod qr;
+mod vault;
+mod redact;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
 pub use app::MyApp;
+#[cfg(not(target_arch = "wasm32"))]
+pub use cli::run as run_cli;
@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Default, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [Self::Debug, Self::Info, Self::Warn, Self::Error];
+
+    pub fn str(&self) -> String {
+        match self {
+            Self::Debug => t("log.debug"),
+            Self::Info => t("log.info"),
+            Self::Warn => t("log.warn"),
+            Self::Error => t("log.error"),
+        }
+        .into()
+    }
+}
+
+/// One line of script output, as sent by the runner.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LogLine {
+    pub time: String,
+    pub account: Option<usize>,
+    pub level: LogLevel,
+    pub text: String,
+}
+
+impl LogLine {
+    pub fn to_text(&self) -> String {
+        let account = self.account.map(|x| format!(" #{x}")).unwrap_or_default();
+        format!(
+            "{} [{}]{} {}",
+            self.time,
+            self.level.str(),
+            account,
+            self.text
+        )
+    }
+}
+
+/// Keeps the latest `capacity` lines, dropping the oldest.
+pub struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(10000)
+    }
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, line: LogLine) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&LogLine> {
+        self.lines.get(idx)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Indices of lines matching `filter`, oldest first.
+    pub fn filtered(&self, filter: &LogFilter) -> Vec<usize> {
+        (0..self.lines.len())
+            .filter(|&i| filter.matches(&self.lines[i]))
+            .collect()
+    }
+
+    pub fn export(&self, filter: &LogFilter) -> String {
+        self.lines
+            .iter()
+            .filter(|x| filter.matches(x))
+            .map(|x| x.to_text() + "\n")
+            .collect()
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct LogFilter {
+    /// Account index, empty for all accounts.
+    pub account: String,
+    /// Lowest level shown.
+    pub level: LogLevel,
+    pub search: String,
+}
+
+impl LogFilter {
+    pub fn matches(&self, line: &LogLine) -> bool {
+        if line.level < self.level {
+            return false;
+        }
+        if let Ok(account) = self.account.trim().parse::<usize>() {
+            if line.account != Some(account) {
+                return false;
+            }
+        }
+        let search = self.search.trim();
+        search.is_empty() || line.text.to_lowercase().contains(&search.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(account: Option<usize>, level: LogLevel, text: &str) -> LogLine {
+        LogLine {
+            time: "12:00:00".into(),
+            account,
+            level,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut log = LogBuffer::new(3);
+        for i in 0..5 {
+            log.push(line(Some(i), LogLevel::Info, &i.to_string()));
+        }
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.get(0).unwrap().text, "2");
+        assert_eq!(log.get(2).unwrap().text, "4");
+    }
+
+    #[test]
+    fn filter() {
+        let mut log = LogBuffer::default();
+        log.push(line(Some(1), LogLevel::Debug, "enter dorm"));
+        log.push(line(Some(1), LogLevel::Warn, "Fight failed"));
+        log.push(line(Some(2), LogLevel::Error, "login failed"));
+        log.push(line(None, LogLevel::Info, "restart"));
+
+        assert_eq!(log.filtered(&LogFilter::default()), vec![1, 2, 3]);
+
+        let mut filter = LogFilter {
+            level: LogLevel::Debug,
+            ..Default::default()
+        };
+        assert_eq!(log.filtered(&filter).len(), 4);
+
+        filter.account = "1".into();
+        assert_eq!(log.filtered(&filter), vec![0, 1]);
+
+        filter.account = "".into();
+        filter.search = "FAILED".into();
+        assert_eq!(log.filtered(&filter), vec![1, 2]);
+
+        filter.level = LogLevel::Error;
+        assert_eq!(log.export(&filter), "12:00:00 [错误] #2 login failed\n");
+    }
+
+    #[test]
+    fn parse() {
+        let lines: Vec<LogLine> = serde_json::from_str(
+            r#"[{"time":"1","level":"warn","text":"a"},{"text":"b","account":3}]"#,
+        )
+        .unwrap();
+        assert_eq!(lines[0].level, LogLevel::Warn);
+        assert_eq!(lines[0].account, None);
+        assert_eq!(lines[1].level, LogLevel::Info);
+        assert_eq!(lines[1].account, Some(3));
+    }
+}
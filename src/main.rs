@@ -7,11 +7,34 @@ fn main() {
     // Log to stdout (if you run with `RUST_LOG=debug`).
     tracing_subscriber::fmt::init();
 
+    // `mizuki_ui <command> ...` runs the command-line tool instead.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = mizuki_ui::run_cli(&args) {
+        std::process::exit(code);
+    }
+
+    // `--config <path>` opens a config file at startup.
+    let mut args = args.into_iter();
+    let mut config = None;
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--config") {
+            Some("") => config = args.next(),
+            Some(x) if x.starts_with('=') => config = Some(x[1..].to_string()),
+            _ => {}
+        }
+    }
+
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
         "mizuki_ui",
         native_options,
-        Box::new(|cc| Box::new(mizuki_ui::MyApp::new(cc))),
+        Box::new(move |cc| {
+            let mut app = mizuki_ui::MyApp::new(cc);
+            if let Some(path) = &config {
+                app.open_config(path);
+            }
+            Box::new(app)
+        }),
     );
 }
 
@@ -0,0 +1,314 @@
+use chrono::Weekday;
+
+use crate::data::{Account, AccountMode};
+use crate::i18n::{t, tf};
+
+/// One task of a run, in the order the script executes them.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Step {
+    Mail,
+    Fight {
+        /// Stages of `fight` open on the day, tried in order.
+        stages: Vec<String>,
+        max_drug: usize,
+        /// `(days until expiry, count)` of extra drugs used before they expire.
+        drug_day: Vec<(usize, usize)>,
+        max_stone: usize,
+    },
+    Friend,
+    Gain,
+    Shift,
+    Manu,
+    Clue,
+    Assist,
+    Shop {
+        prefer: String,
+        dislike: String,
+    },
+    Recruit {
+        /// Message keys of the recruited tags.
+        stars: Vec<&'static str>,
+    },
+    Task,
+    Activity,
+    Zl {
+        max_level: Option<usize>,
+        max_coin: Option<usize>,
+    },
+}
+
+impl Step {
+    pub fn str(&self) -> String {
+        match self {
+            Self::Mail => t("job.mail").into(),
+            Self::Fight {
+                stages,
+                max_drug,
+                drug_day,
+                max_stone,
+            } => {
+                let stages = if stages.is_empty() {
+                    t("step.no_stage").to_string()
+                } else {
+                    stages.join(" ")
+                };
+                let mut text = tf("step.fight", &[&stages, max_drug, max_stone]);
+                for (day, count) in drug_day {
+                    text += &tf("step.drug_day", &[day, count]);
+                }
+                text
+            }
+            Self::Friend => t("job.friend").into(),
+            Self::Gain => t("job.gain").into(),
+            Self::Shift => t("job.shift").into(),
+            Self::Manu => t("job.manu").into(),
+            Self::Clue => t("job.clue").into(),
+            Self::Assist => t("job.assist").into(),
+            Self::Shop { prefer, dislike } => tf("step.shop", &[prefer, dislike]),
+            Self::Recruit { stars } => {
+                let stars: Vec<_> = stars.iter().map(|x| t(x)).collect();
+                tf("step.recruit", &[&stars.join(" ")])
+            }
+            Self::Task => t("job.task").into(),
+            Self::Activity => t("job.activity").into(),
+            Self::Zl {
+                max_level,
+                max_coin,
+            } => {
+                let mut text = t("step.zl").to_string();
+                if let Some(x) = max_level {
+                    text += &tf("step.zl_level", &[x]);
+                }
+                if let Some(x) = max_coin {
+                    text += &tf("step.zl_coin", &[x]);
+                }
+                text
+            }
+        }
+    }
+}
+
+/// Whether a `fight` stage such as `ce-6` or `pr-a-2` is open on `weekday`,
+/// following the resource stage rotation of the game. Unknown stages are
+/// assumed to be always open.
+pub fn stage_open(stage: &str, weekday: Weekday) -> bool {
+    use Weekday::*;
+    let stage = stage.to_lowercase();
+    let mut parts = stage.split('-');
+    let kind = match (parts.next(), parts.next()) {
+        (Some("pr"), Some(x)) if x.len() == 1 && x.chars().all(|c| c.is_ascii_alphabetic()) => {
+            format!("pr-{x}")
+        }
+        (Some(x), _) => x.to_string(),
+        _ => return true,
+    };
+    let days: &[Weekday] = match kind.as_str() {
+        "ce" => &[Tue, Thu, Sat, Sun],
+        "ca" => &[Tue, Wed, Fri, Sun],
+        "ap" => &[Mon, Thu, Sat, Sun],
+        "sk" => &[Mon, Wed, Fri, Sat],
+        "pr-a" => &[Mon, Thu, Fri, Sun],
+        "pr-b" => &[Mon, Tue, Fri, Sat],
+        "pr-c" => &[Wed, Thu, Sat, Sun],
+        "pr-d" => &[Tue, Wed, Sat, Sun],
+        _ => return true,
+    };
+    days.contains(&weekday)
+}
+
+fn recruit_daily(account: &Account) -> Vec<&'static str> {
+    [
+        (account.recruit0, "recruit.other"),
+        (account.recruit1, "recruit.robot"),
+        (account.recruit4, "recruit.star4"),
+        (account.recruit5, "recruit.star5"),
+        (account.recruit6, "recruit.star6"),
+    ]
+    .into_iter()
+    .filter(|x| x.0)
+    .map(|x| x.1)
+    .collect()
+}
+
+fn daily(account: &Account, weekday: Weekday) -> Vec<Step> {
+    let len = account.max_drug_day.len();
+    let steps = [
+        (account.job_mail, Step::Mail),
+        (
+            account.job_fight,
+            Step::Fight {
+                stages: account
+                    .fight
+                    .split_whitespace()
+                    .filter(|x| stage_open(x, weekday))
+                    .map(|x| x.to_string())
+                    .collect(),
+                max_drug: account.max_drug,
+                drug_day: (0..len)
+                    .rev()
+                    .map(|i| (len - i - 1, account.max_drug_day[i]))
+                    .filter(|x| x.1 > 0)
+                    .collect(),
+                max_stone: account.max_stone,
+            },
+        ),
+        (account.job_friend, Step::Friend),
+        (account.job_gain, Step::Gain),
+        (account.job_shift, Step::Shift),
+        (account.job_manu, Step::Manu),
+        (account.job_clue, Step::Clue),
+        (account.job_assist, Step::Assist),
+        (
+            account.job_shop,
+            Step::Shop {
+                prefer: account.prefer_goods.clone(),
+                dislike: account.dislike_goods.clone(),
+            },
+        ),
+        (
+            account.job_recruit,
+            Step::Recruit {
+                stars: recruit_daily(account),
+            },
+        ),
+        (account.job_task, Step::Task),
+        (account.job_activity, Step::Activity),
+    ];
+    steps.into_iter().filter(|x| x.0).map(|x| x.1).collect()
+}
+
+/// The tasks the script runs for an account on `weekday`, or `None` when the
+/// account does not run that day. `account` should already be resolved.
+pub fn plan(account: &Account, weekday: Weekday) -> Option<Vec<Step>> {
+    match account.mode {
+        AccountMode::Daily => {
+            if !account.weekdays()[weekday.num_days_from_monday() as usize] {
+                return None;
+            }
+            Some(daily(account, weekday))
+        }
+        AccountMode::ZL => {
+            let mut steps = if account.zl_no_waste {
+                daily(account, weekday)
+            } else {
+                vec![]
+            };
+            steps.push(Step::Zl {
+                max_level: account.zl_level.then_some(account.zl_max_level),
+                max_coin: account.zl_coin.then_some(account.zl_max_coin),
+            });
+            Some(steps)
+        }
+        AccountMode::Recruit => {
+            let stars = [
+                (true, "recruit.other"),
+                (account.recruit_recruit1, "recruit.robot"),
+                (account.recruit_recruit4, "recruit.star4"),
+                (account.recruit_recruit5, "recruit.star5"),
+                (account.recruit_recruit6, "recruit.star6"),
+            ];
+            Some(vec![Step::Recruit {
+                stars: stars.into_iter().filter(|x| x.0).map(|x| x.1).collect(),
+            }])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountBuilder;
+    use Weekday::*;
+
+    #[test]
+    fn stages() {
+        assert!(stage_open("ce-6", Tue));
+        assert!(!stage_open("CE-6", Mon));
+        assert!(stage_open("pr-a-2", Mon));
+        assert!(!stage_open("pr-a-2", Tue));
+        assert!(!stage_open("pr-d", Mon));
+        assert!(stage_open("pr", Mon));
+        assert!(stage_open("ls-6", Mon));
+        assert!(stage_open("1-7", Mon));
+        assert!(stage_open("jm", Wed));
+    }
+
+    #[test]
+    fn daily_plan() {
+        let account = AccountBuilder::default()
+            .fight("jm ce-6 ap-5 ls-6".into())
+            .max_drug(2)
+            .max_drug_day(vec![0, 1, 0, 0, 0, 9, 99])
+            .job_friend(false)
+            .recruit0(false)
+            .allow_sunday(false)
+            .build()
+            .unwrap();
+
+        assert_eq!(plan(&account, Sun), None);
+
+        let steps = plan(&account, Mon).unwrap();
+        assert_eq!(steps[0], Step::Mail);
+        assert_eq!(
+            steps[1],
+            Step::Fight {
+                stages: vec!["jm".into(), "ap-5".into(), "ls-6".into()],
+                max_drug: 2,
+                drug_day: vec![(0, 99), (1, 9), (5, 1)],
+                max_stone: 0,
+            }
+        );
+        assert_eq!(steps[2], Step::Gain);
+        assert!(steps.contains(&Step::Recruit {
+            stars: vec![
+                "recruit.robot",
+                "recruit.star4",
+                "recruit.star5",
+                "recruit.star6"
+            ]
+        }));
+        assert_eq!(steps.last(), Some(&Step::Activity));
+        assert_eq!(steps.len(), 11);
+
+        let steps = plan(&account, Tue).unwrap();
+        assert!(
+            matches!(&steps[1], Step::Fight { stages, .. } if stages == &["jm", "ce-6", "ls-6"])
+        );
+    }
+
+    #[test]
+    fn other_modes() {
+        let mut account = AccountBuilder::default()
+            .mode(AccountMode::ZL)
+            .zl_coin(false)
+            .zl_max_level(30)
+            .allow_monday(false)
+            .build()
+            .unwrap();
+        let steps = plan(&account, Mon).unwrap();
+        assert_eq!(steps.len(), 13);
+        assert_eq!(
+            steps.last(),
+            Some(&Step::Zl {
+                max_level: Some(30),
+                max_coin: None
+            })
+        );
+        account.zl_no_waste = false;
+        assert_eq!(plan(&account, Mon).unwrap().len(), 1);
+
+        account.mode = AccountMode::Recruit;
+        account.recruit_recruit1 = false;
+        assert_eq!(
+            plan(&account, Mon).unwrap(),
+            vec![Step::Recruit {
+                stars: vec![
+                    "recruit.other",
+                    "recruit.star4",
+                    "recruit.star5",
+                    "recruit.star6"
+                ]
+            }]
+        );
+    }
+}
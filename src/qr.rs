@@ -0,0 +1,132 @@
+use qrcode::{Color, EcLevel, QrCode};
+
+/// Bytes per code, well below the format's limit so phone cameras still read
+/// the codes reliably from a screen.
+pub const CHUNK: usize = 1000;
+/// Starts the header line of each code when the text needs several.
+///
+/// Such a code reads `MIZUKI i/n`, a newline, then its part of the text,
+/// with `i` counting from 1 to the number of codes `n`. The text is the
+/// parts joined in order of `i`, without separators; a reader has it once
+/// it scanned all `n`, in any order. A code without the header holds the
+/// whole text.
+const HEADER: &str = "MIZUKI";
+
+/// A QR code as rows of dark modules, without the quiet zone.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Code {
+    pub width: usize,
+    pub dark: Vec<bool>,
+}
+
+impl Code {
+    pub fn new(text: &str) -> Result<Self, String> {
+        let code =
+            QrCode::with_error_correction_level(text, EcLevel::M).map_err(|e| e.to_string())?;
+        Ok(Self {
+            width: code.width(),
+            dark: code
+                .to_colors()
+                .into_iter()
+                .map(|x| x == Color::Dark)
+                .collect(),
+        })
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.dark[y * self.width + x]
+    }
+}
+
+/// Splits `text` into the texts of the codes showing it. A text that fits one
+/// code is left as it is, otherwise each part starts with a `MIZUKI i/n` line.
+pub fn chunks(text: &str, size: usize) -> Vec<String> {
+    if text.len() <= size {
+        return vec![text.to_string()];
+    }
+    let mut parts = vec![];
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            // `size` is smaller than the next character, which gets a code
+            // of its own
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        parts.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    let n = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| format!("{HEADER} {}/{n}\n{x}", i + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reassembles scanned parts the way the device does, in any order.
+    fn join(parts: &[&str]) -> Option<String> {
+        if let [x] = parts {
+            if !x.starts_with(HEADER) {
+                return Some(x.to_string());
+            }
+        }
+        let mut found = vec![];
+        for part in parts {
+            let (head, body) = part.strip_prefix(HEADER)?.split_once('\n')?;
+            let (i, n) = head.trim().split_once('/')?;
+            let (i, n): (usize, usize) = (i.parse().ok()?, n.parse().ok()?);
+            found.push((i, n, body));
+        }
+        found.sort_by_key(|x| x.0);
+        let n = found.len();
+        let complete = found
+            .iter()
+            .enumerate()
+            .all(|(k, x)| x.0 == k + 1 && x.1 == n);
+        complete.then(|| found.into_iter().map(|x| x.2).collect())
+    }
+
+    #[test]
+    fn chunk_and_join() {
+        assert_eq!(chunks("short", 10), vec!["short"]);
+        assert_eq!(join(&["short"]).unwrap(), "short");
+
+        let text = "账号=一二三四五\nfight=1-7\n".repeat(20);
+        let parts = chunks(&text, 50);
+        assert!(parts.len() > 1);
+        assert!(parts[0].starts_with(&format!("MIZUKI 1/{}\n", parts.len())));
+        let mut scanned: Vec<_> = parts.iter().map(String::as_str).collect();
+        scanned.reverse();
+        assert_eq!(join(&scanned).unwrap(), text);
+
+        scanned.pop();
+        assert!(join(&scanned).is_none());
+        assert!(join(&[parts[0].as_str(), "other"]).is_none());
+
+        // sizes below one character still make progress
+        assert_eq!(chunks("一二", 2), vec!["MIZUKI 1/2\n一", "MIZUKI 2/2\n二"]);
+    }
+
+    #[test]
+    fn codes() {
+        let code = Code::new("MIZUKI").unwrap();
+        assert_eq!(code.width, 21);
+        assert_eq!(code.dark.len(), 21 * 21);
+        // finder pattern corner
+        assert!(code.is_dark(0, 0) && code.is_dark(6, 6) && !code.is_dark(7, 7));
+
+        let text = "x".repeat(3000);
+        assert!(Code::new(&text).is_err());
+        for part in chunks(&text, CHUNK) {
+            assert!(Code::new(&part).is_ok());
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use aho_corasick::{AhoCorasick, MatchKind};
+use serde_json::Value;
+
+use crate::data::{Account, Setting};
+use crate::json::{self, Scope};
+use crate::log::{LogBuffer, LogFilter, LogLevel};
+use crate::validate;
+
+/// Replaces a secret that is not a phone number.
+const MASK: &str = "***";
+/// Shorter credentials are left alone, masking them would garble unrelated text.
+const MIN_SECRET: usize = 4;
+/// Names whose values are masked in `name=value` and `"name": "value"` pairs,
+/// also as the last `_` separated part of a longer name like `access_token`.
+const KEYS: &[&str] = &[
+    "password", "passwd", "pwd", "token", "key", "secret", "sign", "ticket", "cookie", "phone",
+];
+
+/// Whether `text` looks like a mainland mobile number.
+fn is_phone(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() == 11
+        && bytes[0] == b'1'
+        && (b'3'..=b'9').contains(&bytes[1])
+        && bytes.iter().all(u8::is_ascii_digit)
+}
+
+/// How `secret` shows up once redacted. Phone numbers keep their first three
+/// and last four digits so accounts can still be told apart.
+pub fn mask(secret: &str) -> String {
+    if is_phone(secret) {
+        format!("{}****{}", &secret[..3], &secret[7..])
+    } else {
+        MASK.into()
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn phones(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let end = rest[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(rest.len(), |x| start + x);
+        out += &rest[..start];
+        let digits = &rest[start..end];
+        out += &if is_phone(digits) {
+            mask(digits)
+        } else {
+            digits.to_string()
+        };
+        rest = &rest[end..];
+    }
+    out + rest
+}
+
+/// Length of the `=` or `:` between a name and its value, with any quotes
+/// and spaces around it.
+fn separator(text: &str) -> Option<usize> {
+    let rest = text.strip_prefix(['"', '\'']).unwrap_or(text).trim_start();
+    let rest = rest.strip_prefix(['=', ':'])?.trim_start();
+    let rest = rest.strip_prefix(['"', '\'']).unwrap_or(rest);
+    Some(text.len() - rest.len())
+}
+
+fn pairs(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(is_word) {
+        let end = rest[start..]
+            .find(|c| !is_word(c))
+            .map_or(rest.len(), |x| start + x);
+        let name = rest[start..end].to_ascii_lowercase();
+        out += &rest[..end];
+        rest = &rest[end..];
+        let last = name.rsplit('_').next().unwrap_or(&name);
+        if !KEYS.contains(&last) {
+            continue;
+        }
+        let Some(sep) = separator(rest) else {
+            continue;
+        };
+        out += &rest[..sep];
+        rest = &rest[sep..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "&\"',;}".contains(c))
+            .unwrap_or(rest.len());
+        if len > 0 {
+            out += MASK;
+        }
+        rest = &rest[len..];
+    }
+    out + rest
+}
+
+/// Masks secrets in text leaving the app: the credentials of the config
+/// wherever they appear, plus phone numbers and `token=...` style pairs.
+#[derive(Default)]
+pub struct Redactor {
+    /// Finds the known secrets, the longest where they overlap.
+    secrets: Option<AhoCorasick>,
+    masks: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(account: &[Account], setting: &Setting) -> Self {
+        let mut secrets: Vec<_> = account
+            .iter()
+            .flat_map(Account::secrets)
+            .chain(setting.secrets())
+            .map(str::trim)
+            .filter(|x| x.chars().count() >= MIN_SECRET)
+            .collect();
+        secrets.sort_unstable();
+        secrets.dedup();
+        let masks = secrets.iter().map(|x| mask(x)).collect();
+        let secrets = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(&secrets)
+            .ok();
+        Self { secrets, masks }
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        match &self.secrets {
+            Some(x) => pairs(&phones(&x.replace_all(text, &self.masks))),
+            None => pairs(&phones(text)),
+        }
+    }
+
+    /// Masks every string in `value`, keeping the document's structure.
+    pub fn value(&self, value: &mut Value) {
+        match value {
+            Value::String(x) => *x = self.text(x),
+            Value::Array(x) => x.iter_mut().for_each(|x| self.value(x)),
+            Value::Object(x) => x.values_mut().for_each(|x| self.value(x)),
+            _ => {}
+        }
+    }
+}
+
+/// Masks a credential field, leaving it empty when it is not set.
+fn mask_field(secret: &mut String) {
+    if !secret.is_empty() {
+        *secret = mask(secret.trim());
+    }
+}
+
+/// The config with its credentials masked.
+pub fn config(account: &[Account], setting: &Setting) -> (Vec<Account>, Setting) {
+    let mut account = account.to_vec();
+    let mut setting = setting.clone();
+    for x in &mut account {
+        x.secrets_mut().into_iter().for_each(mask_field);
+    }
+    setting.secrets_mut().into_iter().for_each(mask_field);
+    (account, setting)
+}
+
+/// Text without the secrets a redactor knowing nothing of the config finds,
+/// for output that has no config at hand such as the raw API responses
+/// printed by tests.
+#[cfg(test)]
+pub fn text(text: &str) -> String {
+    Redactor::default().text(text)
+}
+
+/// Everything a bug report needs in one text: the platform, what validation
+/// finds, the whole config and the log, all redacted.
+pub fn issue_report(account: &[Account], setting: &Setting, log: &LogBuffer) -> String {
+    let redactor = Redactor::new(account, setting);
+    let mut out = format!(
+        "# Mizuki {}\nplatform: {} {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    );
+
+    out += "\n## diagnostics\n";
+    for x in validate::all(account, setting) {
+        out += &redactor.text(&x.to_text());
+        out += "\n";
+    }
+
+    out += "\n## config\n";
+    let (masked, masked_setting) = config(account, setting);
+    let mut document = json::document(Scope::All, &masked, &masked_setting);
+    redactor.value(&mut document);
+    out += &serde_json::to_string_pretty(&document).unwrap();
+    out += "\n";
+
+    out += "\n## log\n";
+    let filter = LogFilter {
+        level: LogLevel::Debug,
+        ..Default::default()
+    };
+    out += &redactor.text(&log.export(&filter));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::default_account;
+    use crate::log::LogLine;
+
+    const SECRETS: [&str; 6] = [
+        "13812345678",
+        "hunter2!",
+        "capt-user",
+        "s3cret-captcha",
+        "987654321",
+        "https://push.example.com/send/abcdef",
+    ];
+
+    fn sample() -> (Vec<Account>, Setting) {
+        let mut account: Vec<_> = (0..3).map(default_account).collect();
+        account[1].username = SECRETS[0].into();
+        account[1].password = SECRETS[1].into();
+        account[1].fight = "ce-6".into();
+        let setting = Setting {
+            captcha_username: SECRETS[2].into(),
+            captcha_password: SECRETS[3].into(),
+            qq_notify: SECRETS[4].into(),
+            qq_notify_server: SECRETS[5].into(),
+            multi_account: true,
+            multi_account_choice: "1".into(),
+            ..Default::default()
+        };
+        (account, setting)
+    }
+
+    fn assert_clean(text: &str) {
+        for secret in SECRETS {
+            assert!(!text.contains(secret), "{secret} in {text}");
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!(mask("13812345678"), "138****5678");
+        assert_eq!(mask("alice"), MASK);
+        assert_eq!(
+            text("call 13912345678, not 12345678901 or 1391234567890"),
+            "call 139****5678, not 12345678901 or 1391234567890"
+        );
+        assert_eq!(
+            text("GET /send?qq=1&key=abc123&x=2 password: hunter2 end"),
+            "GET /send?qq=1&key=***&x=2 password: *** end"
+        );
+        assert_eq!(
+            text(r#"{"status":0,"data":{"token":"AbC+/=="},"msg":"OK"}"#),
+            r#"{"status":0,"data":{"token":"***"},"msg":"OK"}"#
+        );
+        assert_eq!(
+            text(r#"{"access_token": "xyz", "token": "", "keys": 2}"#),
+            r#"{"access_token": "***", "token": "", "keys": 2}"#
+        );
+        assert_eq!(
+            text("monkey: 3, design=x, sign_key=y"),
+            "monkey: 3, design=x, sign_key=***"
+        );
+        assert_eq!(text("no secrets here"), "no secrets here");
+    }
+
+    #[test]
+    fn known_secrets() {
+        let (account, setting) = sample();
+        let redactor = Redactor::new(&account, &setting);
+        let line = format!(
+            "login {} with {} failed, notify {} via {}",
+            SECRETS[0], SECRETS[1], SECRETS[4], SECRETS[5]
+        );
+        let redacted = redactor.text(&line);
+        assert_clean(&redacted);
+        assert_eq!(
+            redacted,
+            "login 138****5678 with *** failed, notify *** via ***"
+        );
+
+        let mut document = json::document(Scope::All, &account, &setting);
+        redactor.value(&mut document);
+        assert_clean(&document.to_string());
+        assert_eq!(document["account"]["1"]["fight"], "ce-6");
+        assert_eq!(document["account"]["1"]["username"], "138****5678");
+
+        let (masked, masked_setting) = config(&account, &setting);
+        assert_clean(&format!("{masked:?}{masked_setting:?}"));
+        assert_eq!(masked[0].username, "");
+    }
+
+    #[test]
+    fn issue_report_has_no_secrets() {
+        let (account, setting) = sample();
+        let mut log = LogBuffer::default();
+        for (level, text) in [
+            (LogLevel::Debug, format!("password={}", SECRETS[1])),
+            (LogLevel::Info, format!("logged in as {}", SECRETS[0])),
+            (LogLevel::Error, format!("captcha {} rejected", SECRETS[3])),
+        ] {
+            log.push(LogLine {
+                account: Some(1),
+                level,
+                text,
+                ..Default::default()
+            });
+        }
+        let report = issue_report(&account, &setting, &log);
+        assert_clean(&report);
+        assert!(report.contains("## diagnostics"));
+        assert!(report.contains("\"fight\": \"ce-6\""));
+        assert!(report.contains("logged in as 138****5678"));
+        assert!(report.contains("password=***"));
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tf;
+
+/// Summary of one run of one account, as produced by the script or the runner.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RunReport {
+    pub account: usize,
+    pub time: String,
+    /// Tasks completed, e.g. `"job_mail"`.
+    pub tasks: Vec<String>,
+    /// Times each stage was fought.
+    pub stages: BTreeMap<String, usize>,
+    pub sanity: usize,
+    pub failures: Vec<String>,
+}
+
+impl RunReport {
+    pub fn ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn to_text(&self) -> String {
+        let stages = self
+            .stages
+            .iter()
+            .map(|(stage, times)| format!("{stage}x{times}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut text = tf(
+            "report.summary",
+            &[
+                &self.time,
+                &self.tasks.len(),
+                &self.sanity,
+                &if stages.is_empty() { "-" } else { &stages },
+            ],
+        );
+        if !self.ok() {
+            text += &tf("report.failures", &[&self.failures.join(", ")]);
+        }
+        text
+    }
+}
+
+/// Accepts a single report, an array of reports or one report per line.
+pub fn parse(text: &str) -> Result<Vec<RunReport>, String> {
+    let text = text.trim();
+    if text.starts_with('[') {
+        return serde_json::from_str(text).map_err(|e| e.to_string());
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, x)| !x.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| tf("report.bad_line", &[&(i + 1), &e]))
+        })
+        .collect()
+}
+
+/// Latest reports of every account, newest last.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct History {
+    account: BTreeMap<usize, Vec<RunReport>>,
+}
+
+impl History {
+    const MAX_PER_ACCOUNT: usize = 50;
+
+    pub fn add(&mut self, report: RunReport) {
+        let reports = self.account.entry(report.account).or_default();
+        reports.push(report);
+        if reports.len() > Self::MAX_PER_ACCOUNT {
+            reports.remove(0);
+        }
+    }
+
+    pub fn get(&self, idx: usize) -> &[RunReport] {
+        self.account.get(&idx).map(|x| &x[..]).unwrap_or(&[])
+    }
+
+    pub fn last(&self, idx: usize) -> Option<&RunReport> {
+        self.get(idx).last()
+    }
+
+    pub fn clear(&mut self, idx: usize) {
+        self.account.remove(&idx);
+    }
+
+    /// Moves the reports along with their accounts, `map` giving each old
+    /// index its new one.
+    pub fn remap(&mut self, map: impl Fn(usize) -> usize) {
+        self.account = std::mem::take(&mut self.account)
+            .into_iter()
+            .map(|(idx, mut reports)| {
+                let idx = map(idx);
+                reports.iter_mut().for_each(|x| x.account = idx);
+                (idx, reports)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_formats() {
+        let one = r#"{"account":1,"time":"2022-12-01 04:00","tasks":["job_mail"],"stages":{"ce-6":3},"sanity":90}"#;
+        let reports = parse(one).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].stages["ce-6"], 3);
+        assert!(reports[0].ok());
+
+        let lines = format!("{one}\n\n{{\"account\":2,\"failures\":[\"login\"]}}\n");
+        let reports = parse(&lines).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert!(!reports[1].ok());
+
+        let array = format!("[{one}, {one}]");
+        assert_eq!(parse(&array).unwrap().len(), 2);
+
+        assert!(parse("{\"account\":1}\nnot json")
+            .unwrap_err()
+            .starts_with("第2行"));
+    }
+
+    #[test]
+    fn history() {
+        let mut history = History::default();
+        assert!(history.last(3).is_none());
+        for i in 0..60 {
+            history.add(RunReport {
+                account: 3,
+                sanity: i,
+                ..Default::default()
+            });
+        }
+        assert_eq!(history.get(3).len(), History::MAX_PER_ACCOUNT);
+        assert_eq!(history.get(3)[0].sanity, 10);
+        assert_eq!(history.last(3).unwrap().sanity, 59);
+        assert!(history.get(4).is_empty());
+        history.remap(|i| if i == 3 { 4 } else { i });
+        assert!(history.get(3).is_empty());
+        assert_eq!(history.last(4).unwrap().account, 4);
+        history.clear(4);
+        assert!(history.get(4).is_empty());
+    }
+}
@@ -0,0 +1,386 @@
+use ehttp::Request;
+use poll_promise::Promise;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{Account, Setting};
+use crate::i18n::{t, tf};
+use crate::log::LogLine;
+use crate::report::RunReport;
+
+#[derive(Deserialize, Serialize, PartialEq, Default, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum RunState {
+    #[default]
+    Idle,
+    Starting,
+    Running,
+    Stopped,
+    Error(String),
+}
+
+impl RunState {
+    pub fn str(&self) -> String {
+        match self {
+            Self::Idle => t("run.idle").into(),
+            Self::Starting => t("run.starting").into(),
+            Self::Running => t("run.running").into(),
+            Self::Stopped => t("run.stopped").into(),
+            Self::Error(e) => tf("run.error", &[e]),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Starting | Self::Running)
+    }
+}
+
+/// What the runtime receives on start, the same shape as the UI state.
+#[derive(Serialize)]
+pub struct RunRequest<'a> {
+    pub account: &'a [Account],
+    pub setting: &'a Setting,
+}
+
+/// Output of a run other than its state.
+#[derive(PartialEq, Clone, Debug)]
+pub enum RunEvent {
+    Log(LogLine),
+    Report(RunReport),
+}
+
+/// A Mizuki runtime that can be asked to run the current configuration.
+pub trait Runner {
+    fn start(&mut self, request: &RunRequest<'_>);
+    fn stop(&mut self);
+    /// Called every frame, `time` is in seconds.
+    fn poll(&mut self, time: f64) -> RunState;
+    /// Events received since the last call.
+    fn drain(&mut self) -> Vec<RunEvent> {
+        Vec::new()
+    }
+}
+
+#[derive(Deserialize)]
+struct Status {
+    state: RunState,
+}
+
+pub fn parse_status(text: &str) -> RunState {
+    match serde_json::from_str::<Status>(text) {
+        Ok(status) => status.state,
+        Err(e) => RunState::Error(e.to_string()),
+    }
+}
+
+fn fetch(request: Request) -> Promise<Result<String, String>> {
+    let (sender, promise) = Promise::new();
+    ehttp::fetch(request, move |result: ehttp::Result<ehttp::Response>| {
+        let result = result.and_then(|response| {
+            if response.ok {
+                Ok(response.text().unwrap_or("").to_string())
+            } else {
+                Err(format!("{} {}", response.status, response.status_text))
+            }
+        });
+        sender.send(result);
+    });
+    promise
+}
+
+/// An endpoint answering a json array of the items after the first `since`.
+struct Feed {
+    path: &'static str,
+    pending: Option<Promise<Result<String, String>>>,
+    since: usize,
+    last: f64,
+    /// Fetch once more right away, for items written after the last fetch
+    /// of a run that just ended.
+    flush: bool,
+}
+
+impl Feed {
+    fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            pending: None,
+            since: 0,
+            last: 0.0,
+            flush: false,
+        }
+    }
+
+    fn poll<T: DeserializeOwned>(&mut self, url: &str, time: f64) -> Vec<T> {
+        let mut items = Vec::new();
+        if let Some(promise) = &self.pending {
+            if let Some(result) = promise.ready() {
+                if let Ok(Ok(x)) = result
+                    .as_ref()
+                    .map(|text| serde_json::from_str::<Vec<T>>(text))
+                {
+                    self.since += x.len();
+                    items = x;
+                }
+                self.pending = None;
+                self.last = time;
+            }
+        } else if self.flush || time - self.last > HttpRunner::STATUS_INTERVAL {
+            self.flush = false;
+            let url = format!("{}{}?since={}", url, self.path, self.since);
+            self.pending = Some(fetch(Request::get(url)));
+        }
+        items
+    }
+}
+
+/// Runtime listening on a local http endpoint:
+/// `POST /start` with a [`RunRequest`], `POST /stop` and `GET /status`,
+/// each answering `{"state": ...}`, and `GET /log?since=n` and
+/// `GET /report?since=n` answering the [`LogLine`]s and [`RunReport`]s
+/// after the first `n`.
+pub struct HttpRunner {
+    url: String,
+    state: RunState,
+    pending: Option<Promise<Result<String, String>>>,
+    last_status: f64,
+    log: Feed,
+    report: Feed,
+    events: Vec<RunEvent>,
+}
+
+impl HttpRunner {
+    const STATUS_INTERVAL: f64 = 1.0;
+
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            state: RunState::Idle,
+            pending: None,
+            last_status: 0.0,
+            log: Feed::new("/log"),
+            report: Feed::new("/report"),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Runner for HttpRunner {
+    fn start(&mut self, request: &RunRequest<'_>) {
+        let body = serde_json::to_vec(request).unwrap_or_default();
+        let mut request = Request::post(format!("{}/start", self.url), body);
+        request
+            .headers
+            .insert("Content-Type".into(), "application/json".into());
+        self.pending = Some(fetch(request));
+        self.state = RunState::Starting;
+    }
+
+    fn stop(&mut self) {
+        let request = Request::post(format!("{}/stop", self.url), vec![]);
+        self.pending = Some(fetch(request));
+    }
+
+    fn poll(&mut self, time: f64) -> RunState {
+        if let Some(promise) = &self.pending {
+            if let Some(result) = promise.ready() {
+                let was_active = self.state.is_active();
+                self.state = match result {
+                    Ok(text) => parse_status(text),
+                    Err(e) => RunState::Error(e.clone()),
+                };
+                self.pending = None;
+                self.last_status = time;
+                if was_active && !self.state.is_active() {
+                    self.log.flush = true;
+                    self.report.flush = true;
+                }
+            }
+        } else if self.state.is_active() && time - self.last_status > Self::STATUS_INTERVAL {
+            self.pending = Some(fetch(Request::get(format!("{}/status", self.url))));
+        }
+        if self.state.is_active() || self.log.pending.is_some() || self.log.flush {
+            let lines = self.log.poll(&self.url, time);
+            self.events.extend(lines.into_iter().map(RunEvent::Log));
+        }
+        if self.state.is_active() || self.report.pending.is_some() || self.report.flush {
+            let reports = self.report.poll(&self.url, time);
+            self.events
+                .extend(reports.into_iter().map(RunEvent::Report));
+        }
+        self.state.clone()
+    }
+
+    fn drain(&mut self) -> Vec<RunEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Owns the runner of the current run, if any.
+#[derive(Default)]
+pub struct RunControl {
+    runner: Option<Box<dyn Runner>>,
+    state: RunState,
+}
+
+impl RunControl {
+    pub fn state(&self) -> &RunState {
+        &self.state
+    }
+
+    pub fn start(&mut self, mut runner: Box<dyn Runner>, request: &RunRequest<'_>) {
+        if self.state.is_active() {
+            return;
+        }
+        runner.start(request);
+        self.state = RunState::Starting;
+        self.runner = Some(runner);
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(runner) = &mut self.runner {
+            runner.stop();
+        }
+    }
+
+    pub fn poll(&mut self, time: f64) -> &RunState {
+        if let Some(runner) = &mut self.runner {
+            self.state = runner.poll(time);
+        }
+        &self.state
+    }
+
+    pub fn drain(&mut self) -> Vec<RunEvent> {
+        self.runner
+            .as_mut()
+            .map(|runner| runner.drain())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountBuilder;
+
+    /// Starts on the first poll after `start`, stops on the first poll after `stop`.
+    #[derive(Default)]
+    struct FakeRunner {
+        state: RunState,
+        fail: Option<String>,
+        received: Option<String>,
+    }
+
+    impl Runner for FakeRunner {
+        fn start(&mut self, request: &RunRequest<'_>) {
+            self.received = Some(serde_json::to_string(request).unwrap());
+            self.state = RunState::Starting;
+        }
+        fn stop(&mut self) {
+            self.state = RunState::Stopped;
+        }
+        fn poll(&mut self, _time: f64) -> RunState {
+            if self.state == RunState::Starting {
+                self.state = match &self.fail {
+                    Some(e) => RunState::Error(e.clone()),
+                    None => RunState::Running,
+                };
+            }
+            self.state.clone()
+        }
+        fn drain(&mut self) -> Vec<RunEvent> {
+            match self.state {
+                RunState::Running => vec![RunEvent::Log(LogLine {
+                    text: "running".into(),
+                    ..Default::default()
+                })],
+                _ => vec![],
+            }
+        }
+    }
+
+    fn request_parts() -> (Vec<Account>, Setting) {
+        let account = vec![AccountBuilder::default()
+            .username("a".into())
+            .build()
+            .unwrap()];
+        (account, Setting::default())
+    }
+
+    #[test]
+    fn feed_flush() {
+        let url = "http://127.0.0.1:9";
+        let mut feed = Feed::new("/log");
+        feed.last = 5.0;
+        assert!(feed.poll::<LogLine>(url, 5.1).is_empty());
+        assert!(feed.pending.is_none());
+        // the run ended: fetch at once, regardless of the interval
+        feed.flush = true;
+        feed.poll::<LogLine>(url, 5.2);
+        assert!(feed.pending.is_some() && !feed.flush);
+    }
+
+    #[test]
+    fn status() {
+        assert_eq!(parse_status(r#"{"state":"running"}"#), RunState::Running);
+        assert_eq!(parse_status(r#"{"state":"stopped"}"#), RunState::Stopped);
+        assert_eq!(
+            parse_status(r#"{"state":{"error":"no device"}}"#),
+            RunState::Error("no device".into())
+        );
+        assert!(matches!(parse_status("oops"), RunState::Error(_)));
+    }
+
+    #[test]
+    fn start_and_stop() {
+        let (account, setting) = request_parts();
+        let request = RunRequest {
+            account: &account,
+            setting: &setting,
+        };
+        let mut control = RunControl::default();
+        assert_eq!(control.poll(0.0), &RunState::Idle);
+
+        control.start(Box::<FakeRunner>::default(), &request);
+        assert_eq!(control.state(), &RunState::Starting);
+        assert!(control.drain().is_empty());
+        assert_eq!(control.poll(0.1), &RunState::Running);
+        assert!(matches!(&control.drain()[..], [RunEvent::Log(x)] if x.text == "running"));
+
+        // a second start while running is ignored
+        control.start(Box::<FakeRunner>::default(), &request);
+        assert_eq!(control.poll(0.2), &RunState::Running);
+
+        control.stop();
+        assert_eq!(control.poll(0.3), &RunState::Stopped);
+        assert!(!control.state().is_active());
+    }
+
+    #[test]
+    fn start_error() {
+        let (account, setting) = request_parts();
+        let request = RunRequest {
+            account: &account,
+            setting: &setting,
+        };
+        let mut control = RunControl::default();
+        let runner = FakeRunner {
+            fail: Some("no device".into()),
+            ..Default::default()
+        };
+        control.start(Box::new(runner), &request);
+        assert_eq!(control.poll(0.0), &RunState::Error("no device".into()));
+    }
+
+    #[test]
+    fn request_body() {
+        let (account, setting) = request_parts();
+        let mut runner = FakeRunner::default();
+        runner.start(&RunRequest {
+            account: &account,
+            setting: &setting,
+        });
+        let body: serde_json::Value = serde_json::from_str(&runner.received.unwrap()).unwrap();
+        assert_eq!(body["account"][0]["username"], "a");
+        assert_eq!(body["setting"]["crontab"], setting.crontab);
+    }
+}
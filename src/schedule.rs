@@ -0,0 +1,184 @@
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+
+use crate::choice;
+use crate::data::{daily_source, Account, AccountMode, Setting};
+use crate::i18n::tf;
+
+/// Message keys of the weekdays, Monday first.
+pub const WEEKDAY: [&str; 7] = [
+    "weekday.mon",
+    "weekday.tue",
+    "weekday.wed",
+    "weekday.thu",
+    "weekday.fri",
+    "weekday.sat",
+    "weekday.sun",
+];
+
+/// Restart times of `Setting.crontab`, e.g. `"4:00 12:00 20:00"`, sorted.
+pub fn crontab(text: &str) -> Result<Vec<NaiveTime>, String> {
+    let mut times = text
+        .split_whitespace()
+        .map(|x| NaiveTime::parse_from_str(x, "%H:%M").map_err(|_| tf("schedule.bad_time", &[&x])))
+        .collect::<Result<Vec<_>, _>>()?;
+    times.sort();
+    times.dedup();
+    Ok(times)
+}
+
+pub fn allow_after(text: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M")
+        .map_err(|_| tf("schedule.bad_date", &[&text]))
+}
+
+/// Whether the account at `idx` may run at `at`, judged by the weekday
+/// toggles and `allow_after` it uses after inheritance.
+pub fn allowed(account: &[Account], idx: usize, at: NaiveDateTime) -> bool {
+    if account[idx].mode != AccountMode::Daily {
+        return true;
+    }
+    let daily = &account[daily_source(account, idx)];
+    if !daily.weekdays()[at.weekday().num_days_from_monday() as usize] {
+        return false;
+    }
+    allow_after(&daily.allow_after)
+        .map(|after| at >= after)
+        .unwrap_or(true)
+}
+
+pub struct Tick {
+    pub time: NaiveDateTime,
+    /// Accounts processed at this restart.
+    pub account: Vec<usize>,
+}
+
+/// Every crontab restart in `[from, from + days)` with the accounts it processes.
+pub fn ticks(
+    account: &[Account],
+    setting: &Setting,
+    from: NaiveDateTime,
+    days: i64,
+) -> Result<Vec<Tick>, String> {
+    let times = crontab(&setting.crontab)?;
+    let candidates: Vec<usize> = if setting.multi_account {
+        choice::parse(&setting.multi_account_choice)?
+            .indices(account.len())
+            .into_iter()
+            .filter(|&i| setting.multi_account_allow_empty || !account[i].username.is_empty())
+            .collect()
+    } else {
+        vec![0]
+    };
+
+    let end = from + Duration::days(days);
+    let mut ticks = Vec::new();
+    for day in 0..=days {
+        let date = from.date() + Duration::days(day);
+        for time in &times {
+            let time = date.and_time(*time);
+            if time < from || time >= end {
+                continue;
+            }
+            let account = candidates
+                .iter()
+                .copied()
+                .filter(|&i| allowed(account, i, time))
+                .collect();
+            ticks.push(Tick { time, account });
+        }
+    }
+    Ok(ticks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountBuilder;
+
+    fn at(text: &str) -> NaiveDateTime {
+        allow_after(text).unwrap()
+    }
+
+    #[test]
+    fn parse_crontab() {
+        let times = crontab(" 20:00 4:00  12:00 4:00").unwrap();
+        assert_eq!(
+            times,
+            vec![
+                NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+            ]
+        );
+        assert!(crontab("").unwrap().is_empty());
+        assert!(crontab("4:00 25:00").is_err());
+    }
+
+    #[test]
+    fn weekday_and_allow_after() {
+        let account = vec![
+            AccountBuilder::default()
+                .inherit(false)
+                .allow_sunday(false)
+                .allow_after("2022-12-01 12:00".into())
+                .build()
+                .unwrap(),
+            AccountBuilder::default().build().unwrap(),
+            AccountBuilder::default()
+                .mode(AccountMode::ZL)
+                .build()
+                .unwrap(),
+        ];
+        // 2022-12-01 is a Thursday
+        assert!(!allowed(&account, 0, at("2022-12-01 04:00")));
+        assert!(allowed(&account, 0, at("2022-12-01 12:00")));
+        assert!(!allowed(&account, 0, at("2022-12-04 12:00")));
+        // inherits account 0
+        assert!(!allowed(&account, 1, at("2022-12-04 12:00")));
+        // not a daily account
+        assert!(allowed(&account, 2, at("2022-12-04 12:00")));
+    }
+
+    #[test]
+    fn week() {
+        let mut account = vec![AccountBuilder::default()
+            .inherit(false)
+            .allow_after("2022-01-01 00:00".into())
+            .build()
+            .unwrap()];
+        account.extend(vec![AccountBuilder::default().build().unwrap(); 4]);
+        account[0].allow_monday = false;
+        account[3].username = "x".into();
+        account[3].inherit = false;
+        account[3].allow_after = "2022-01-01 00:00".into();
+
+        let setting = Setting {
+            multi_account: true,
+            multi_account_choice: "0-3".into(),
+            ..Default::default()
+        };
+        let ticks = ticks(&account, &setting, at("2022-12-01 08:00"), 7).unwrap();
+        // Thu 12:00 to Thu 04:00 a week later
+        assert_eq!(ticks.len(), 21);
+        assert_eq!(ticks[0].time, at("2022-12-01 12:00"));
+        assert_eq!(ticks[20].time, at("2022-12-08 04:00"));
+        assert_eq!(ticks[0].account, vec![0, 1, 2, 3]);
+        // Monday 2022-12-05
+        let monday = ticks.iter().find(|x| x.time == at("2022-12-05 04:00"));
+        assert_eq!(monday.unwrap().account, vec![3]);
+
+        let setting = Setting {
+            multi_account_allow_empty: false,
+            ..setting
+        };
+        let ticks = super::ticks(&account, &setting, at("2022-12-01 08:00"), 7).unwrap();
+        assert_eq!(ticks[0].account, vec![3]);
+
+        let setting = Setting {
+            multi_account: false,
+            ..setting
+        };
+        let ticks = super::ticks(&account, &setting, at("2022-12-01 08:00"), 7).unwrap();
+        assert_eq!(ticks[0].account, vec![0]);
+    }
+}
@@ -0,0 +1,218 @@
+use base64::alphabet::URL_SAFE;
+use base64::engine::fast_portable::{FastPortable, NO_PAD};
+use serde_json::Value;
+
+use crate::data::{Account, Setting};
+use crate::i18n::t;
+use crate::json::{self, Scope};
+use crate::redact::Redactor;
+
+const ENGINE: FastPortable = FastPortable::from(&URL_SAFE, NO_PAD);
+/// Marks a share code and its format version.
+const PREFIX: &str = "mz1.";
+/// Name of the URL fragment parameter holding a share code.
+const KEY: &str = "share=";
+/// Largest config a share code may unpack to.
+const LIMIT: usize = 4 << 20;
+/// Settings that belong to this machine, never taken from a share: the
+/// runner receives every credential, so a shared URL could collect them.
+pub const LOCAL: &[&str] = &["runner_url", "language"];
+
+/// Removes every credential, so only settings are shared.
+pub fn strip(account: &[Account], setting: &Setting) -> (Vec<Account>, Setting) {
+    let mut account = account.to_vec();
+    let mut setting = setting.clone();
+    for x in &mut account {
+        x.secrets_mut().into_iter().for_each(String::clear);
+    }
+    setting.secrets_mut().into_iter().for_each(String::clear);
+    (account, setting)
+}
+
+/// A short URL-safe code of the config without credentials: its sparse JSON
+/// document, deflated and base64 encoded. Credentials copied into other
+/// fields are redacted as well, and machine-local settings left out.
+pub fn encode(account: &[Account], setting: &Setting) -> String {
+    let redactor = Redactor::new(account, setting);
+    let (account, setting) = strip(account, setting);
+    let mut document = json::document(Scope::All, &account, &setting);
+    if let Some(x) = document["setting"].as_object_mut() {
+        LOCAL.iter().for_each(|key| {
+            x.remove(*key);
+        });
+    }
+    redactor.value(&mut document);
+    let packed = miniz_oxide::deflate::compress_to_vec(document.to_string().as_bytes(), 9);
+    format!("{PREFIX}{}", base64::encode_engine(packed, &ENGINE))
+}
+
+/// `base` with `code` in its fragment.
+pub fn link(base: &str, code: &str) -> String {
+    let base = base.split('#').next().unwrap_or(base);
+    format!("{base}#{KEY}{code}")
+}
+
+/// Reads a code made by [`encode`], or a link holding one, into a whole
+/// config document for [`merge`].
+pub fn decode(text: &str, total: usize) -> Result<Value, String> {
+    let text = text.trim();
+    let code = match text.find(KEY) {
+        Some(i) => text[i + KEY.len()..].split('&').next().unwrap_or(""),
+        None => text,
+    };
+    let invalid = || t("share.invalid").to_string();
+    let code = code.strip_prefix(PREFIX).ok_or_else(invalid)?;
+    let packed = base64::decode_engine(code, &ENGINE).map_err(|_| invalid())?;
+    let bytes = miniz_oxide::inflate::decompress_to_vec_with_limit(&packed, LIMIT)
+        .map_err(|_| invalid())?;
+    let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+    json::parse(Scope::All, &text, total)
+}
+
+/// The config after applying a shared `document`, keeping the credentials
+/// and machine-local settings already entered here since the share carries
+/// none.
+pub fn merge(document: &Value, account: &[Account], setting: &Setting) -> (Vec<Account>, Setting) {
+    let mut merged = account.to_vec();
+    let mut merged_setting = setting.clone();
+    json::apply(Scope::All, document, &mut merged, &mut merged_setting);
+    for (x, old) in merged.iter_mut().zip(account) {
+        let mut old = old.clone();
+        for (x, old) in x.secrets_mut().into_iter().zip(old.secrets_mut()) {
+            *x = std::mem::take(old);
+        }
+    }
+    let mut old = setting.clone();
+    for (x, old) in merged_setting
+        .secrets_mut()
+        .into_iter()
+        .zip(old.secrets_mut())
+    {
+        *x = std::mem::take(old);
+    }
+    keep_local(&mut merged_setting, setting);
+    (merged, merged_setting)
+}
+
+/// Puts the [`LOCAL`] settings of `local` back into `setting`, read from
+/// somewhere that does not carry them.
+pub fn keep_local(setting: &mut Setting, local: &Setting) {
+    setting.runner_url = local.runner_url.clone();
+    setting.language = local.language;
+}
+
+/// The share code in the page's URL fragment, removed from the address bar
+/// so reloading does not import it again.
+#[cfg(target_arch = "wasm32")]
+pub fn take_from_url() -> Option<String> {
+    let location = web_sys::window()?.location();
+    let hash = location.hash().ok()?;
+    if !hash.contains(KEY) {
+        return None;
+    }
+    location.set_hash("").ok()?;
+    Some(hash)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn take_from_url() -> Option<String> {
+    None
+}
+
+/// The page address to put share codes in, on the web.
+#[cfg(target_arch = "wasm32")]
+pub fn base_url() -> Option<String> {
+    web_sys::window()?.location().href().ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn base_url() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::default_account;
+    use crate::i18n::Lang;
+
+    fn config() -> (Vec<Account>, Setting) {
+        let mut account: Vec<_> = (0..3).map(default_account).collect();
+        account[0].username = "13800000000".into();
+        account[0].password = "secret".into();
+        account[0].fight = "ce-6".into();
+        account[1].fight = "13800000000".into();
+        account[2].username = "only credentials".into();
+        let setting = Setting {
+            captcha_password: "hunter2".into(),
+            qq_notify: "12345".into(),
+            crontab: "5:00".into(),
+            runner_url: "https://attacker.example.com".into(),
+            language: Lang::En,
+            ..Default::default()
+        };
+        (account, setting)
+    }
+
+    #[test]
+    fn no_secrets() {
+        let (account, setting) = config();
+        let code = encode(&account, &setting);
+        assert!(code.starts_with(PREFIX));
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)));
+        let document = decode(&code, 3).unwrap();
+        let text = document.to_string();
+        for secret in [
+            "13800000000",
+            "secret",
+            "hunter2",
+            "12345",
+            "only",
+            "attacker",
+        ] {
+            assert!(!text.contains(secret), "{secret}");
+        }
+        assert_eq!(document["account"].as_object().unwrap().len(), 2);
+        assert_eq!(document["account"]["0"]["fight"], "ce-6");
+        assert_eq!(document["account"]["1"]["fight"], "138****0000");
+    }
+
+    #[test]
+    fn merge_keeps_credentials() {
+        let (account, setting) = config();
+        let code = encode(&account, &setting);
+        let url = link("https://example.com/mizuki/#old", &code);
+        assert_eq!(url, format!("https://example.com/mizuki/#share={code}"));
+
+        let mut local: Vec<_> = (0..3).map(default_account).collect();
+        local[2].username = "mine".into();
+        local[2].fight = "1-7".into();
+        let local_setting = Setting {
+            qq_notify: "99999".into(),
+            runner_url: "http://10.0.0.2:8611".into(),
+            language: Lang::En,
+            ..Default::default()
+        };
+        let document = decode(&url, 3).unwrap();
+        let (merged, merged_setting) = merge(&document, &local, &local_setting);
+        assert_eq!(merged[0].fight, "ce-6");
+        assert_eq!(merged[0].username, "");
+        assert_eq!(merged[2].username, "mine");
+        assert_eq!(merged[2].fight, default_account(2).fight);
+        assert_eq!(merged_setting.crontab, "5:00");
+        assert_eq!(merged_setting.qq_notify, "99999");
+        assert_eq!(merged_setting.runner_url, "http://10.0.0.2:8611");
+        assert_eq!(merged_setting.language, Lang::En);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(decode("", 3).is_err());
+        assert!(decode("mz1.!!", 3).is_err());
+        assert!(decode("mz1.AAAA", 3).is_err());
+        let code = format!("{PREFIX}{}", base64::encode_engine(b"x", &ENGINE));
+        assert!(decode(&code, 3).is_err());
+    }
+}
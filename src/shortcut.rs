@@ -0,0 +1,93 @@
+use egui::{InputState, Key, KeyboardShortcut, Modifiers};
+
+use crate::i18n::t;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Action {
+    NextAccount,
+    PrevAccount,
+    Jump,
+    ToggleSetting,
+    Save,
+    Undo,
+    Redo,
+    CheatSheet,
+}
+
+impl Action {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::NextAccount => t("shortcut.next"),
+            Self::PrevAccount => t("shortcut.prev"),
+            Self::Jump => t("shortcut.jump"),
+            Self::ToggleSetting => t("shortcut.setting"),
+            Self::Save => t("shortcut.save"),
+            Self::Undo => t("shortcut.undo"),
+            Self::Redo => t("shortcut.redo"),
+            Self::CheatSheet => t("shortcut.cheat_sheet"),
+        }
+    }
+}
+
+/// Every key binding, in cheat-sheet order. `COMMAND` is Ctrl, or Cmd on Mac.
+pub const SHORTCUTS: &[(Action, KeyboardShortcut)] = &[
+    (
+        Action::NextAccount,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::ArrowDown),
+    ),
+    (
+        Action::PrevAccount,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::ArrowUp),
+    ),
+    (
+        Action::Jump,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::G),
+    ),
+    (
+        Action::ToggleSetting,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::E),
+    ),
+    (
+        Action::Save,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::S),
+    ),
+    (
+        Action::Undo,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::Z),
+    ),
+    (
+        Action::Redo,
+        KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z),
+    ),
+    (
+        Action::Redo,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::Y),
+    ),
+    (
+        Action::CheatSheet,
+        KeyboardShortcut::new(Modifiers::NONE, Key::F1),
+    ),
+];
+
+/// Consumes the shortcuts pressed this frame, before any widget sees them.
+pub fn pressed(input: &mut InputState) -> Vec<Action> {
+    SHORTCUTS
+        .iter()
+        .filter(|(_, shortcut)| input.consume_shortcut(shortcut))
+        .map(|(action, _)| *action)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique() {
+        for (i, (action, shortcut)) in SHORTCUTS.iter().enumerate() {
+            for (other, other_shortcut) in &SHORTCUTS[i + 1..] {
+                assert_ne!(shortcut, other_shortcut, "{action:?} and {other:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use crate::data::{Account, Setting};
+
+/// Edits to the same targets closer than this many seconds form one step.
+const GROUP_SECS: f64 = 1.0;
+const MAX_STEPS: usize = 200;
+
+#[derive(PartialEq, Clone, Debug)]
+enum Change {
+    Account {
+        idx: usize,
+        before: Account,
+        after: Account,
+    },
+    Setting {
+        before: Setting,
+        after: Setting,
+    },
+}
+
+impl Change {
+    fn target(&self) -> Option<usize> {
+        match self {
+            Self::Account { idx, .. } => Some(*idx),
+            Self::Setting { .. } => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Step {
+    changes: Vec<Change>,
+    time: f64,
+}
+
+impl Step {
+    fn targets(&self) -> Vec<Option<usize>> {
+        self.changes.iter().map(Change::target).collect()
+    }
+}
+
+/// Undo history of the configuration, recorded by comparing it on every frame
+/// that may have edited it with the copy taken last time, so edits anywhere
+/// in the UI are covered.
+#[derive(Default)]
+pub struct Undo {
+    account: Vec<Account>,
+    setting: Setting,
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    /// Counts the changes seen, so caches derived from the configuration
+    /// know when to rebuild.
+    revision: u64,
+}
+
+impl Undo {
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Records what changed since the last call as one step, merged into the
+    /// previous step when it edits the same targets within [`GROUP_SECS`].
+    pub fn track(&mut self, account: &[Account], setting: &Setting, time: f64) {
+        if self.account.len() != account.len() {
+            self.account = account.to_vec();
+            self.setting = setting.clone();
+            self.revision += 1;
+            return;
+        }
+        let mut changes = Vec::new();
+        for (idx, (before, after)) in self.account.iter_mut().zip(account).enumerate() {
+            if before != after {
+                changes.push(Change::Account {
+                    idx,
+                    before: std::mem::replace(before, after.clone()),
+                    after: after.clone(),
+                });
+            }
+        }
+        if &self.setting != setting {
+            changes.push(Change::Setting {
+                before: std::mem::replace(&mut self.setting, setting.clone()),
+                after: setting.clone(),
+            });
+        }
+        if changes.is_empty() {
+            return;
+        }
+        self.revision += 1;
+        self.redo.clear();
+
+        let step = Step { changes, time };
+        if let Some(last) = self.undo.last_mut() {
+            if time - last.time < GROUP_SECS && last.targets() == step.targets() {
+                for (old, new) in last.changes.iter_mut().zip(step.changes) {
+                    match (old, new) {
+                        (Change::Account { after, .. }, Change::Account { after: x, .. }) => {
+                            *after = x
+                        }
+                        (Change::Setting { after, .. }, Change::Setting { after: x, .. }) => {
+                            *after = x
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                last.time = time;
+                return;
+            }
+        }
+        self.undo.push(step);
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+    }
+
+    fn apply(&mut self, step: &Step, account: &mut [Account], setting: &mut Setting, back: bool) {
+        self.revision += 1;
+        for change in &step.changes {
+            match change {
+                Change::Account { idx, before, after } => {
+                    let x = if back { before } else { after };
+                    account[*idx] = x.clone();
+                    self.account[*idx] = x.clone();
+                }
+                Change::Setting { before, after } => {
+                    let x = if back { before } else { after };
+                    *setting = x.clone();
+                    self.setting = x.clone();
+                }
+            }
+        }
+    }
+
+    pub fn undo(&mut self, account: &mut [Account], setting: &mut Setting) -> bool {
+        match self.undo.pop() {
+            Some(step) => {
+                self.apply(&step, account, setting, true);
+                self.redo.push(step);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, account: &mut [Account], setting: &mut Setting) -> bool {
+        match self.redo.pop() {
+            Some(mut step) => {
+                self.apply(&step, account, setting, false);
+                // never merge a redone step with the next edit
+                step.time = f64::NEG_INFINITY;
+                self.undo.push(step);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountBuilder;
+
+    #[test]
+    fn undo_redo() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 3];
+        let mut setting = Setting::default();
+        let mut undo = Undo::default();
+        undo.track(&account, &setting, 0.0);
+        assert!(!undo.can_undo());
+
+        // typing into one field is grouped into one step
+        account[1].fight = "1".into();
+        undo.track(&account, &setting, 1.0);
+        account[1].fight = "1-".into();
+        undo.track(&account, &setting, 1.5);
+        account[1].fight = "1-7".into();
+        undo.track(&account, &setting, 2.0);
+        // another target starts a new step
+        account[2].max_drug = 3;
+        undo.track(&account, &setting, 2.1);
+        // so does a pause
+        account[2].max_drug = 4;
+        undo.track(&account, &setting, 5.0);
+        setting.crontab = "4:00".into();
+        undo.track(&account, &setting, 5.1);
+
+        assert!(undo.undo(&mut account, &mut setting));
+        assert_eq!(setting.crontab, Setting::default().crontab);
+        assert!(undo.undo(&mut account, &mut setting));
+        assert_eq!(account[2].max_drug, 3);
+        assert!(undo.undo(&mut account, &mut setting));
+        assert_eq!(account[2].max_drug, 0);
+        // nothing changed by undoing itself
+        undo.track(&account, &setting, 5.2);
+        assert!(undo.can_redo());
+        assert!(undo.undo(&mut account, &mut setting));
+        assert_eq!(account[1].fight, account[0].fight);
+        assert!(!undo.undo(&mut account, &mut setting));
+
+        assert!(undo.redo(&mut account, &mut setting));
+        assert_eq!(account[1].fight, "1-7");
+        assert!(undo.redo(&mut account, &mut setting));
+        assert_eq!(account[2].max_drug, 3);
+
+        // a new edit drops the redo steps
+        account[0].max_stone = 1;
+        undo.track(&account, &setting, 5.3);
+        assert!(!undo.can_redo());
+        assert!(!undo.redo(&mut account, &mut setting));
+        assert!(undo.undo(&mut account, &mut setting));
+        assert_eq!(account[0].max_stone, 0);
+        assert_eq!(account[2].max_drug, 3);
+    }
+
+    #[test]
+    fn bulk_step() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 4];
+        let mut setting = Setting::default();
+        let mut undo = Undo::default();
+        undo.track(&account, &setting, 0.0);
+        let revision = undo.revision();
+        undo.track(&account, &setting, 0.5);
+        assert_eq!(undo.revision(), revision);
+        for x in &mut account {
+            x.max_drug = 9;
+        }
+        undo.track(&account, &setting, 1.0);
+        assert_eq!(undo.revision(), revision + 1);
+        assert!(undo.undo(&mut account, &mut setting));
+        assert_eq!(undo.revision(), revision + 2);
+        assert!(account.iter().all(|x| x.max_drug == 0));
+        assert!(!undo.can_undo());
+    }
+}
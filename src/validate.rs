@@ -0,0 +1,332 @@
+use crate::choice;
+use crate::data::{daily_source, Account, AccountMode, Server, Setting};
+use crate::i18n::{t, tf};
+use crate::schedule;
+use crate::vault;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Warning => t("log.warn"),
+            Self::Error => t("log.error"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Target {
+    Account(usize),
+    Setting,
+}
+
+/// A problem with one field, keyed by its serde name like the help entries.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Diagnostic {
+    pub target: Target,
+    pub field: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn to_text(&self) -> String {
+        let target = match self.target {
+            Target::Account(idx) => format!("#{idx}"),
+            Target::Setting => t("section.setting").into(),
+        };
+        format!(
+            "[{}] {target} {}: {}",
+            self.severity.str(),
+            self.field,
+            self.message
+        )
+    }
+}
+
+struct Collector {
+    target: Target,
+    list: Vec<Diagnostic>,
+}
+
+impl Collector {
+    fn push(&mut self, field: &'static str, severity: Severity, message: String) {
+        self.list.push(Diagnostic {
+            target: self.target,
+            field,
+            severity,
+            message,
+        });
+    }
+}
+
+/// Stage tokens of `fight`: letters, digits and single inner dashes, e.g. `ce-6`.
+fn stage_valid(stage: &str) -> bool {
+    stage
+        .split('-')
+        .all(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+fn digits(text: &str, len: std::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&text.len()) && text.chars().all(|c| c.is_ascii_digit())
+}
+
+fn url_valid(text: &str) -> bool {
+    text.starts_with("http://") || text.starts_with("https://")
+}
+
+/// Problems of the account at `idx`. Credentials are only checked in
+/// multi-account mode, where they are entered.
+pub fn account(account: &[Account], idx: usize, setting: &Setting) -> Vec<Diagnostic> {
+    let mut out = Collector {
+        target: Target::Account(idx),
+        list: vec![],
+    };
+    let x = &account[idx];
+
+    if setting.multi_account {
+        if x.server == Server::Official && !x.username.is_empty() && !digits(&x.username, 11..=11) {
+            out.push("username", Severity::Warning, t("validate.phone").into());
+        }
+        if !x.username.is_empty() && x.password.is_empty() {
+            out.push(
+                "password",
+                Severity::Warning,
+                t("validate.no_password").into(),
+            );
+        }
+    }
+
+    if vault::is_encrypted(&x.password) {
+        out.push("password", Severity::Error, t("validate.locked").into());
+    }
+
+    let own = x.mode != AccountMode::Daily || !x.inherit;
+    if x.mode == AccountMode::Daily && x.inherit {
+        if x.inherit_index >= account.len() {
+            out.push(
+                "inherit_index",
+                Severity::Error,
+                tf("validate.no_account", &[&x.inherit_index]),
+            );
+        } else {
+            let source = &account[daily_source(account, idx)];
+            if source.mode == AccountMode::Daily && source.inherit {
+                let message = if source.inherit_index >= account.len() {
+                    t("validate.broken_chain")
+                } else {
+                    t("validate.cycle")
+                };
+                out.push("inherit_index", Severity::Error, message.into());
+            }
+        }
+    }
+    if own {
+        for stage in x.fight.split_whitespace() {
+            if !stage_valid(stage) {
+                out.push(
+                    "fight",
+                    Severity::Error,
+                    tf("validate.bad_stage", &[&stage]),
+                );
+            }
+        }
+        if x.mode == AccountMode::Daily && x.job_fight && x.fight.trim().is_empty() {
+            out.push("fight", Severity::Warning, t("validate.no_stage").into());
+        }
+        if !x.allow_after.trim().is_empty() {
+            if let Err(e) = schedule::allow_after(&x.allow_after) {
+                out.push("allow_after", Severity::Error, e);
+            }
+        }
+    }
+    out.list
+}
+
+pub fn setting(setting: &Setting, total: usize) -> Vec<Diagnostic> {
+    let mut out = Collector {
+        target: Target::Setting,
+        list: vec![],
+    };
+    for (field, value) in [
+        ("captcha_password", &setting.captcha_password),
+        ("qq_notify", &setting.qq_notify),
+        ("qq_notify_server", &setting.qq_notify_server),
+    ] {
+        if vault::is_encrypted(value) {
+            out.push(field, Severity::Error, t("validate.locked").into());
+        }
+    }
+    if let Err(e) = choice::parse(&setting.multi_account_choice) {
+        out.push("multi_account_choice", Severity::Error, e);
+    }
+    if let Err(e) = schedule::crontab(&setting.crontab) {
+        out.push("crontab", Severity::Error, e);
+    }
+    let clue = setting.multi_account_clue.trim();
+    if !clue.is_empty() && !matches!(clue.parse::<usize>(), Ok(x) if x < total) {
+        out.push(
+            "multi_account_clue",
+            Severity::Error,
+            tf("validate.bad_index", &[&clue]),
+        );
+    }
+    if !setting.qq_notify.is_empty() && !digits(&setting.qq_notify, 5..=11) {
+        out.push("qq_notify", Severity::Error, t("validate.qq").into());
+    }
+    if !setting.qq_notify_server.is_empty() && !url_valid(&setting.qq_notify_server) {
+        out.push(
+            "qq_notify_server",
+            Severity::Warning,
+            t("validate.url").into(),
+        );
+    }
+    if !url_valid(&setting.runner_url) {
+        out.push("runner_url", Severity::Error, t("validate.url").into());
+    }
+    if !setting.captcha_username.is_empty() && setting.captcha_password.is_empty() {
+        out.push(
+            "captcha_password",
+            Severity::Warning,
+            t("validate.no_password").into(),
+        );
+    }
+    out.list
+}
+
+/// Problems of everything a run would use: the setting, then the accounts
+/// the run processes.
+pub fn all(account: &[Account], setting: &Setting) -> Vec<Diagnostic> {
+    let mut list = self::setting(setting, account.len());
+    let indices = if setting.multi_account {
+        match choice::parse(&setting.multi_account_choice) {
+            Ok(x) => x.indices(account.len()),
+            Err(_) => vec![],
+        }
+    } else {
+        vec![0]
+    };
+    for idx in indices {
+        list.extend(self::account(account, idx, setting));
+    }
+    list
+}
+
+/// The most severe problem of `field` in `list`.
+pub fn worst<'a>(list: &'a [Diagnostic], field: &str) -> Option<&'a Diagnostic> {
+    list.iter()
+        .filter(|x| x.field == field)
+        .max_by_key(|x| x.severity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::AccountBuilder;
+
+    fn fields(list: &[Diagnostic]) -> Vec<(&str, Severity)> {
+        list.iter().map(|x| (x.field, x.severity)).collect()
+    }
+
+    #[test]
+    fn accounts() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 4];
+        account[0].inherit = false;
+        let setting = Setting {
+            multi_account: true,
+            ..Default::default()
+        };
+        for i in 0..4 {
+            assert!(self::account(&account, i, &setting).is_empty());
+        }
+
+        account[0].username = "abc".into();
+        account[0].fight = "ce-6 pr-a-2 1-7 ce--6 -x".into();
+        account[0].allow_after = "2022-13-01".into();
+        assert_eq!(
+            fields(&self::account(&account, 0, &setting)),
+            vec![
+                ("username", Severity::Warning),
+                ("password", Severity::Warning),
+                ("fight", Severity::Error),
+                ("fight", Severity::Error),
+                ("allow_after", Severity::Error),
+            ]
+        );
+        account[0].password = "enc1.x.y".into();
+        assert!(self::account(&account, 0, &setting)
+            .iter()
+            .any(|x| x.field == "password" && x.severity == Severity::Error));
+        account[0].password = String::new();
+        // inherited settings are checked on the account they come from
+        account[1].fight = "!".into();
+        assert!(self::account(&account, 1, &setting).is_empty());
+
+        account[2].inherit_index = 3;
+        account[3].inherit_index = 2;
+        assert_eq!(
+            fields(&self::account(&account, 2, &setting)),
+            vec![("inherit_index", Severity::Error)]
+        );
+        let cycle = self::account(&account, 2, &setting);
+        assert_eq!(cycle[0].message, t("validate.cycle"));
+        account[3].inherit_index = 9;
+        assert_eq!(
+            fields(&self::account(&account, 3, &setting)),
+            vec![("inherit_index", Severity::Error)]
+        );
+        // a chain ending at a bad index is no cycle
+        let broken = self::account(&account, 2, &setting);
+        assert_eq!(broken[0].message, t("validate.broken_chain"));
+    }
+
+    #[test]
+    fn settings() {
+        assert!(setting(&Setting::default(), 10).is_empty());
+        let x = Setting {
+            multi_account_choice: "0-x".into(),
+            crontab: "25:00".into(),
+            multi_account_clue: "10".into(),
+            qq_notify: "12ab".into(),
+            qq_notify_server: "example.com".into(),
+            runner_url: "".into(),
+            captcha_username: "u".into(),
+            ..Default::default()
+        };
+        let list = setting(&x, 10);
+        assert_eq!(
+            fields(&list),
+            vec![
+                ("multi_account_choice", Severity::Error),
+                ("crontab", Severity::Error),
+                ("multi_account_clue", Severity::Error),
+                ("qq_notify", Severity::Error),
+                ("qq_notify_server", Severity::Warning),
+                ("runner_url", Severity::Error),
+                ("captcha_password", Severity::Warning),
+            ]
+        );
+        assert_eq!(worst(&list, "crontab").unwrap().severity, Severity::Error);
+        assert!(worst(&list, "fight").is_none());
+    }
+
+    #[test]
+    fn run() {
+        let mut account = vec![AccountBuilder::default().build().unwrap(); 3];
+        account[0].inherit = false;
+        account[2].inherit = false;
+        account[2].fight = "?".into();
+        let mut setting = Setting::default();
+        assert!(all(&account, &setting).is_empty());
+        setting.multi_account = true;
+        setting.multi_account_choice = "1-2".into();
+        assert_eq!(all(&account, &setting).len(), 1);
+        setting.multi_account_choice = "0-1".into();
+        assert!(all(&account, &setting).is_empty());
+    }
+}
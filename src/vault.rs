@@ -0,0 +1,302 @@
+use base64::alphabet::URL_SAFE;
+use base64::engine::fast_portable::{FastPortable, NO_PAD};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use hmac::Hmac;
+use poll_promise::Promise;
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::data::{Account, Setting};
+use crate::i18n::t;
+
+const ENGINE: FastPortable = FastPortable::from(&URL_SAFE, NO_PAD);
+/// Marks an encrypted value and its format version. The PBKDF2 rounds, salt
+/// and sealed text follow, separated by `.`.
+const PREFIX: &str = "enc1.";
+/// PBKDF2 rounds deriving new keys from the passphrase. Values carry their
+/// own count, so raising it keeps older values readable.
+const ROUNDS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Whether `text` is a value written by [`Vault::encrypt`].
+pub fn is_encrypted(text: &str) -> bool {
+    text.starts_with(PREFIX)
+}
+
+/// A key derived from the passphrase with one salt and round count.
+struct Key {
+    rounds: u32,
+    salt: [u8; SALT_LEN],
+    cipher: ChaCha20Poly1305,
+}
+
+impl Key {
+    fn derive(passphrase: &str, rounds: u32, salt: [u8; SALT_LEN]) -> Self {
+        let mut key = [0; 32];
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(passphrase.as_bytes(), &salt, rounds, &mut key);
+        Self {
+            rounds,
+            salt,
+            cipher: ChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(&key)),
+        }
+    }
+}
+
+/// Keys derived from the user's passphrase. Each encrypted value carries its
+/// salt and round count, so one value per key is enough to unlock the rest.
+pub struct Vault {
+    /// The first key encrypts, the others read values written before with
+    /// another salt, as in configs merged from several files.
+    keys: Vec<Key>,
+}
+
+impl Vault {
+    /// A vault with a fresh salt.
+    pub fn new(passphrase: &str) -> Self {
+        let mut salt = [0; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self {
+            keys: vec![Key::derive(passphrase, ROUNDS, salt)],
+        }
+    }
+
+    /// Derives the keys `samples`, encrypted values, were written with and
+    /// checks the passphrase against each of them.
+    pub fn unlock(passphrase: &str, samples: &[String]) -> Result<Self, String> {
+        let mut vault = Self { keys: vec![] };
+        for sample in samples {
+            let (rounds, salt, _) = split(sample)?;
+            if vault.key(rounds, salt).is_none() {
+                vault.keys.push(Key::derive(passphrase, rounds, salt));
+                vault.decrypt(sample)?;
+            }
+        }
+        if vault.keys.is_empty() {
+            return Err(t("vault.invalid").into());
+        }
+        Ok(vault)
+    }
+
+    fn key(&self, rounds: u32, salt: [u8; SALT_LEN]) -> Option<&Key> {
+        self.keys
+            .iter()
+            .find(|x| x.rounds == rounds && x.salt == salt)
+    }
+
+    /// Encrypts `text`, leaving empty and already encrypted values alone.
+    pub fn encrypt(&self, text: &str) -> String {
+        if text.is_empty() || is_encrypted(text) {
+            return text.to_string();
+        }
+        let key = &self.keys[0];
+        let mut nonce = [0; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let sealed = key
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), text.as_bytes())
+            .unwrap();
+        let salt = base64::encode_engine(key.salt, &ENGINE);
+        let body = base64::encode_engine([&nonce[..], &sealed].concat(), &ENGINE);
+        format!("{PREFIX}{}.{salt}.{body}", key.rounds)
+    }
+
+    /// Decrypts `text`, returning plain values as they are.
+    pub fn decrypt(&self, text: &str) -> Result<String, String> {
+        if !is_encrypted(text) {
+            return Ok(text.to_string());
+        }
+        let (rounds, salt, body) = split(text)?;
+        let key = match self.key(rounds, salt) {
+            Some(x) if body.len() >= NONCE_LEN => x,
+            _ => return Err(t("vault.wrong").into()),
+        };
+        let (nonce, sealed) = body.split_at(NONCE_LEN);
+        let plain = key
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| t("vault.wrong").to_string())?;
+        String::from_utf8(plain).map_err(|_| t("vault.invalid").into())
+    }
+}
+
+/// A vault whose keys are still being derived.
+pub type Deriving = Promise<Result<Vault, String>>;
+
+/// Runs `f` off the UI: on a thread natively. The web has no threads, so
+/// there it runs once the page got to draw the frame showing the wait.
+#[cfg(not(target_arch = "wasm32"))]
+fn derive_later(f: impl FnOnce() -> Result<Vault, String> + Send + 'static) -> Deriving {
+    Promise::spawn_thread("vault", f)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn derive_later(f: impl FnOnce() -> Result<Vault, String> + 'static) -> Deriving {
+    let (sender, promise) = Promise::new();
+    wasm_bindgen_futures::spawn_local(async move {
+        let drawn = js_sys::Promise::new(&mut |resolve, _| {
+            let waiting = web_sys::window()
+                .map(|x| x.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, 50));
+            if !matches!(waiting, Some(Ok(_))) {
+                let _ = resolve.call0(&wasm_bindgen::JsValue::NULL);
+            }
+        });
+        let _ = wasm_bindgen_futures::JsFuture::from(drawn).await;
+        sender.send(f());
+    });
+    promise
+}
+
+/// [`Vault::new`] without blocking the UI.
+pub fn new_later(passphrase: String) -> Deriving {
+    derive_later(move || Ok(Vault::new(&passphrase)))
+}
+
+/// [`Vault::unlock`] without blocking the UI.
+pub fn unlock_later(passphrase: String, samples: Vec<String>) -> Deriving {
+    derive_later(move || Vault::unlock(&passphrase, &samples))
+}
+
+fn split(text: &str) -> Result<(u32, [u8; SALT_LEN], Vec<u8>), String> {
+    let invalid = || t("vault.invalid").to_string();
+    let mut parts = text.strip_prefix(PREFIX).ok_or_else(invalid)?.split('.');
+    let (Some(rounds), Some(salt), Some(body), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let rounds = rounds.parse().ok().filter(|&x| x > 0).ok_or_else(invalid)?;
+    let salt = base64::decode_engine(salt, &ENGINE).map_err(|_| invalid())?;
+    let body = base64::decode_engine(body, &ENGINE).map_err(|_| invalid())?;
+    Ok((rounds, salt.try_into().map_err(|_| invalid())?, body))
+}
+
+/// One encrypted value of the config for each key it was written with,
+/// which [`Vault::unlock`] needs. Empty when nothing is encrypted.
+pub fn locked(account: &[Account], setting: &Setting) -> Vec<String> {
+    let mut out: Vec<String> = vec![];
+    let values = setting
+        .vaulted()
+        .into_iter()
+        .chain(account.iter().flat_map(Account::vaulted));
+    for x in values.filter(|x| is_encrypted(x)) {
+        let header = |x: &str| x.rsplit_once('.').map(|(header, _)| header.to_string());
+        if !out.iter().any(|y| header(y) == header(x)) {
+            out.push(x.to_string());
+        }
+    }
+    out
+}
+
+/// Encrypts every secret of the config.
+pub fn encrypt_all(vault: &Vault, account: &mut [Account], setting: &mut Setting) {
+    for x in account {
+        for x in x.vaulted_mut() {
+            *x = vault.encrypt(x);
+        }
+    }
+    for x in setting.vaulted_mut() {
+        *x = vault.encrypt(x);
+    }
+}
+
+/// Decrypts every secret of the config, or none if any fails.
+pub fn decrypt_all(
+    vault: &Vault,
+    account: &mut [Account],
+    setting: &mut Setting,
+) -> Result<(), String> {
+    let mut plain_account = account.to_vec();
+    let mut plain_setting = setting.clone();
+    for x in &mut plain_account {
+        for x in x.vaulted_mut() {
+            *x = vault.decrypt(x)?;
+        }
+    }
+    for x in plain_setting.vaulted_mut() {
+        *x = vault.decrypt(x)?;
+    }
+    account.clone_from_slice(&plain_account);
+    *setting = plain_setting;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::default_account;
+
+    #[test]
+    fn encrypt_and_unlock() {
+        let vault = Vault::new("correct horse");
+        let sealed = vault.encrypt("secret");
+        assert!(is_encrypted(&sealed) && !sealed.contains("secret"));
+        assert!(sealed.starts_with(&format!("{PREFIX}{ROUNDS}.")));
+        assert_ne!(vault.encrypt("secret"), sealed);
+        assert_eq!(vault.encrypt(""), "");
+        assert_eq!(vault.encrypt(&sealed), sealed);
+        assert_eq!(vault.decrypt(&sealed).unwrap(), "secret");
+        assert_eq!(vault.decrypt("plain").unwrap(), "plain");
+
+        let samples = [sealed.clone()];
+        let unlocked = Vault::unlock("correct horse", &samples).unwrap();
+        assert_eq!(unlocked.decrypt(&sealed).unwrap(), "secret");
+        assert!(Vault::unlock("wrong", &samples).is_err());
+        assert!(Vault::new("correct horse").decrypt(&sealed).is_err());
+        // a tampered value fails authentication
+        let mut tampered = sealed.clone();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == 'A' { 'B' } else { 'A' });
+        assert!(vault.decrypt(&tampered).is_err());
+        assert!(Vault::unlock("x", &["enc1.bad".into()]).is_err());
+        assert!(Vault::unlock("x", &[]).is_err());
+    }
+
+    #[test]
+    fn whole_config() {
+        let vault = Vault::new("pass");
+        let mut account: Vec<_> = (0..3).map(default_account).collect();
+        account[1].username = "user".into();
+        account[1].password = "pw".into();
+        let mut setting = Setting {
+            captcha_password: "captcha".into(),
+            qq_notify_server: "https://example.com/?key=abc".into(),
+            ..Default::default()
+        };
+        let plain = (account.clone(), setting.clone());
+        assert!(locked(&account, &setting).is_empty());
+
+        encrypt_all(&vault, &mut account, &mut setting);
+        assert_eq!(account[1].username, "user");
+        assert!(is_encrypted(&account[1].password));
+        assert!(is_encrypted(&setting.qq_notify_server));
+        assert_eq!(setting.qq_notify, "");
+        let samples = locked(&account, &setting);
+        assert_eq!(samples.len(), 1);
+
+        let other = Vault::new("pass");
+        assert!(decrypt_all(&other, &mut account, &mut setting).is_err());
+        assert!(is_encrypted(&account[1].password));
+
+        let vault = Vault::unlock("pass", &samples).unwrap();
+        decrypt_all(&vault, &mut account, &mut setting).unwrap();
+        assert_eq!((account, setting), plain);
+    }
+
+    #[test]
+    fn mixed_salts() {
+        // accounts pasted from another file encrypted with a different salt
+        let mut account: Vec<_> = (0..3).map(default_account).collect();
+        account[1].password = Vault::new("pass").encrypt("one");
+        account[2].password = Vault::new("pass").encrypt("two");
+        let mut setting = Setting::default();
+        let samples = locked(&account, &setting);
+        assert_eq!(samples.len(), 2);
+
+        let vault = Vault::unlock("pass", &samples).unwrap();
+        decrypt_all(&vault, &mut account, &mut setting).unwrap();
+        assert_eq!(account[1].password, "one");
+        assert_eq!(account[2].password, "two");
+    }
+}